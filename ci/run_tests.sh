@@ -18,6 +18,7 @@ set -e
 echo "Is Rust from nightly: $IS_NIGHTLY"
 
 cargo check --no-default-features
+cargo check --no-default-features --features persia-speedy-derive
 cargo build
 
 if [ "$IS_NIGHTLY" = "1" ]; then
@@ -27,5 +28,7 @@ else
     cargo test
 fi
 
+cargo test --features tokio,tokio-util
+
 cd speedy-derive
 cargo test
@@ -14,6 +14,7 @@ extern crate quote;
 
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
 
 trait IterExt: Iterator + Sized {
@@ -47,6 +48,26 @@ mod kw {
     syn::custom_keyword!(skip);
     syn::custom_keyword!(constant_prefix);
     syn::custom_keyword!(peek_tag);
+    syn::custom_keyword!(length_delimited);
+    syn::custom_keyword!(other);
+    syn::custom_keyword!(extensible);
+    syn::custom_keyword!(since);
+    syn::custom_keyword!(until);
+    syn::custom_keyword!(bits);
+    syn::custom_keyword!(varint);
+    syn::custom_keyword!(zigzag);
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(read_with);
+    syn::custom_keyword!(write_with);
+    syn::custom_keyword!(minimum_bytes);
+    syn::custom_keyword!(default);
+    syn::custom_keyword!(validate);
+    syn::custom_keyword!(validate_on_write);
+    syn::custom_keyword!(max_length);
+    syn::custom_keyword!(range);
+    syn::custom_keyword!(bound);
+    syn::custom_keyword!(read);
+    syn::custom_keyword!(write);
 
     syn::custom_keyword!(u7);
     syn::custom_keyword!(u8);
@@ -62,6 +83,85 @@ enum Trait {
     Writable,
 }
 
+/// Explicit `where` predicates from a `bound` attribute which replace the inferred ones.
+#[derive(Clone, Default)]
+struct Bound {
+    read: Option<Vec<syn::WherePredicate>>,
+    write: Option<Vec<syn::WherePredicate>>,
+}
+
+impl Bound {
+    fn get(&self, trait_variant: Trait) -> Option<&[syn::WherePredicate]> {
+        match trait_variant {
+            Trait::Readable => self.read.as_deref(),
+            Trait::Writable => self.write.as_deref(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.read.is_none() && self.write.is_none()
+    }
+}
+
+fn parse_where_predicates(literal: &syn::LitStr) -> syn::parse::Result<Vec<syn::WherePredicate>> {
+    let predicates = literal.parse_with(
+        syn::punctuated::Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated,
+    )?;
+    Ok(predicates.into_iter().collect())
+}
+
+/// Parses either `= "..."` or `(read = "...", write = "...")` after the `bound` keyword.
+fn parse_bound(input: &syn::parse::ParseStream) -> syn::parse::Result<Bound> {
+    let mut bound = Bound::default();
+    if input.peek(Token![=]) {
+        let _: Token![=] = input.parse()?;
+        let literal: syn::LitStr = input.parse()?;
+        let predicates = parse_where_predicates(&literal)?;
+        bound.read = Some(predicates.clone());
+        bound.write = Some(predicates);
+        return Ok(bound);
+    }
+
+    let content;
+    parenthesized!( content in input );
+    while !content.is_empty() {
+        let lookahead = content.lookahead1();
+        if lookahead.peek(kw::read) {
+            let key_token = content.parse::<kw::read>()?;
+            let _: Token![=] = content.parse()?;
+            if bound.read.is_some() {
+                return Err(syn::Error::new(key_token.span(), "Duplicate 'read'"));
+            }
+            bound.read = Some(parse_where_predicates(&content.parse()?)?);
+        } else if lookahead.peek(kw::write) {
+            let key_token = content.parse::<kw::write>()?;
+            let _: Token![=] = content.parse()?;
+            if bound.write.is_some() {
+                return Err(syn::Error::new(key_token.span(), "Duplicate 'write'"));
+            }
+            bound.write = Some(parse_where_predicates(&content.parse()?)?);
+        } else {
+            return Err(lookahead.error());
+        }
+
+        if !content.is_empty() {
+            let _: Token![,] = content.parse()?;
+        }
+    }
+
+    Ok(bound)
+}
+
+/// Checks whether the given type refers to the type for which we're deriving the traits,
+/// in which case its bound would be recursive.
+fn mentions_ident(tokens: TokenStream, ident: &syn::Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(ref token_ident) => token_ident == ident,
+        proc_macro2::TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
 fn possibly_uses_generic_ty(generic_types: &[&syn::Ident], ty: &syn::Type) -> bool {
     match ty {
         syn::Type::Path(syn::TypePath {
@@ -166,11 +266,29 @@ fn test_possibly_uses_generic_ty() {
     assert_test!(true, *const T);
     assert_test!(true, [T; 2]);
     assert_test!(true, Vec<T>);
+    assert_test!(true, T::Id);
+    assert_test!(true, <T as Trait>::Id);
+}
+
+#[test]
+fn test_mentions_ident() {
+    let ident = syn::Ident::new("Tree", proc_macro2::Span::call_site());
+    assert!(mentions_ident(quote! { Tree<T> }, &ident));
+    assert!(mentions_ident(quote! { Vec<Tree<T>> }, &ident));
+    assert!(mentions_ident(
+        quote! { Option<Box<(u8, Tree<T>)>> },
+        &ident
+    ));
+    assert!(!mentions_ident(quote! { Vec<T> }, &ident));
+    assert!(!mentions_ident(quote! { Trees<T> }, &ident));
 }
 
 fn common_tokens(
     ast: &syn::DeriveInput,
     types: &[syn::Type],
+    field_predicates: &[syn::WherePredicate],
+    bound: &Bound,
+    versioned: bool,
     trait_variant: Trait,
 ) -> (TokenStream, TokenStream, TokenStream) {
     let impl_params = {
@@ -198,8 +316,9 @@ fn common_tokens(
 
     let generics: Vec<_> = ast.generics.type_params().map(|ty| &ty.ident).collect();
     let where_clause = {
-        let constraints = types.iter().filter_map(|ty| {
-            let possibly_generic = possibly_uses_generic_ty(&generics, ty);
+        let inferred = types.iter().filter_map(|ty| {
+            let possibly_generic = possibly_uses_generic_ty(&generics, ty)
+                && !mentions_ident(ty.to_token_stream(), &ast.ident);
             match (trait_variant, possibly_generic) {
                 (Trait::Readable, true) => Some(quote! { #ty: persia_speedy::Readable< 'a_, C_ > }),
                 (Trait::Readable, false) => None,
@@ -208,6 +327,13 @@ fn common_tokens(
             }
         });
 
+        let constraints: Vec<_> = match bound.get(trait_variant) {
+            Some(bound) => bound.iter().map(|pred| quote! { #pred }).collect(),
+            None => inferred
+                .chain(field_predicates.iter().map(|pred| quote! { #pred }))
+                .collect(),
+        };
+
         let mut predicates = Vec::new();
         if let Some(where_clause) = ast.generics.where_clause.as_ref() {
             predicates = where_clause
@@ -217,6 +343,10 @@ fn common_tokens(
                 .collect();
         }
 
+        if versioned {
+            predicates.push(quote! { C_: persia_speedy::Versioned });
+        }
+
         if trait_variant == Trait::Readable {
             for lifetime in ast.generics.lifetimes() {
                 predicates.push(quote! { 'a_: #lifetime });
@@ -224,7 +354,10 @@ fn common_tokens(
             }
         }
 
-        let items = constraints.chain(predicates.into_iter()).collect_vec();
+        let items = constraints
+            .into_iter()
+            .chain(predicates.into_iter())
+            .collect_vec();
         if items.is_empty() {
             quote! {}
         } else {
@@ -248,6 +381,11 @@ enum BasicType {
 const DEFAULT_LENGTH_TYPE: BasicType = BasicType::U32;
 const DEFAULT_ENUM_TAG_TYPE: BasicType = BasicType::U32;
 
+const PRIMITIVE_TYPES: &[&str] = &[
+    "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32",
+    "u64", "u128", "usize",
+];
+
 impl syn::parse::Parse for BasicType {
     fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
         let lookahead = input.lookahead1();
@@ -279,9 +417,25 @@ impl syn::parse::Parse for BasicType {
 
 enum VariantAttribute {
     Tag { key_token: kw::tag, tag: u64 },
+    Other { key_token: kw::other },
 }
 
-enum StructAttribute {}
+enum StructAttribute {
+    Validate {
+        key_token: kw::validate,
+        path: syn::Path,
+    },
+    ValidateOnWrite {
+        key_token: kw::validate_on_write,
+    },
+    Bound {
+        key_token: kw::bound,
+        bound: Bound,
+    },
+    Extensible {
+        key_token: kw::extensible,
+    },
+}
 
 enum EnumAttribute {
     TagType {
@@ -291,6 +445,23 @@ enum EnumAttribute {
     PeekTag {
         key_token: kw::peek_tag,
     },
+    LengthDelimited {
+        key_token: kw::length_delimited,
+    },
+    Validate {
+        key_token: kw::validate,
+        path: syn::Path,
+    },
+    ValidateOnWrite {
+        key_token: kw::validate_on_write,
+    },
+    Bound {
+        key_token: kw::bound,
+        bound: Bound,
+    },
+    Bits {
+        key_token: kw::bits,
+    },
 }
 
 enum VariantOrStructAttribute {
@@ -311,6 +482,9 @@ fn parse_variant_attribute(
             .map_err(|err| syn::Error::new(raw_tag.span(), err))?;
 
         VariantAttribute::Tag { key_token, tag }
+    } else if lookahead.peek(kw::other) {
+        let key_token = input.parse::<kw::other>()?;
+        VariantAttribute::Other { key_token }
     } else {
         return Ok(None);
     };
@@ -319,10 +493,30 @@ fn parse_variant_attribute(
 }
 
 fn parse_struct_attribute(
-    _input: &syn::parse::ParseStream,
-    _lookahead: &syn::parse::Lookahead1,
+    input: &syn::parse::ParseStream,
+    lookahead: &syn::parse::Lookahead1,
 ) -> syn::parse::Result<Option<StructAttribute>> {
-    Ok(None)
+    let attribute = if lookahead.peek(kw::validate) {
+        let key_token = input.parse::<kw::validate>()?;
+        let _: Token![=] = input.parse()?;
+        let path: syn::Path = input.parse()?;
+
+        StructAttribute::Validate { key_token, path }
+    } else if lookahead.peek(kw::validate_on_write) {
+        let key_token = input.parse::<kw::validate_on_write>()?;
+        StructAttribute::ValidateOnWrite { key_token }
+    } else if lookahead.peek(kw::bound) {
+        let key_token = input.parse::<kw::bound>()?;
+        let bound = parse_bound(input)?;
+        StructAttribute::Bound { key_token, bound }
+    } else if lookahead.peek(kw::extensible) {
+        let key_token = input.parse::<kw::extensible>()?;
+        StructAttribute::Extensible { key_token }
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some(attribute))
 }
 
 fn parse_enum_attribute(
@@ -338,6 +532,25 @@ fn parse_enum_attribute(
     } else if lookahead.peek(kw::peek_tag) {
         let key_token = input.parse::<kw::peek_tag>()?;
         EnumAttribute::PeekTag { key_token }
+    } else if lookahead.peek(kw::length_delimited) {
+        let key_token = input.parse::<kw::length_delimited>()?;
+        EnumAttribute::LengthDelimited { key_token }
+    } else if lookahead.peek(kw::validate) {
+        let key_token = input.parse::<kw::validate>()?;
+        let _: Token![=] = input.parse()?;
+        let path: syn::Path = input.parse()?;
+
+        EnumAttribute::Validate { key_token, path }
+    } else if lookahead.peek(kw::validate_on_write) {
+        let key_token = input.parse::<kw::validate_on_write>()?;
+        EnumAttribute::ValidateOnWrite { key_token }
+    } else if lookahead.peek(kw::bound) {
+        let key_token = input.parse::<kw::bound>()?;
+        let bound = parse_bound(input)?;
+        EnumAttribute::Bound { key_token, bound }
+    } else if lookahead.peek(kw::bits) {
+        let key_token = input.parse::<kw::bits>()?;
+        EnumAttribute::Bits { key_token }
     } else {
         return Ok(None);
     };
@@ -376,13 +589,24 @@ impl syn::parse::Parse for VariantOrStructAttribute {
 
 struct VariantAttributes {
     tag: Option<u64>,
+    other: bool,
 }
 
-struct StructAttributes {}
+struct StructAttributes {
+    validate: Option<syn::Path>,
+    validate_on_write: bool,
+    bound: Bound,
+    extensible: bool,
+}
 
 struct EnumAttributes {
     tag_type: Option<BasicType>,
     peek_tag: bool,
+    length_delimited: bool,
+    validate: Option<syn::Path>,
+    validate_on_write: bool,
+    bound: Bound,
+    bits: Option<kw::bits>,
 }
 
 fn parse_attributes<T>(attrs: &[syn::Attribute]) -> Result<Vec<T>, syn::Error>
@@ -422,6 +646,7 @@ fn collect_variant_attributes(
     attrs: Vec<VariantAttribute>,
 ) -> Result<VariantAttributes, syn::Error> {
     let mut variant_tag = None;
+    let mut other = false;
     for attr in attrs {
         match attr {
             VariantAttribute::Tag { key_token, tag } => {
@@ -431,21 +656,79 @@ fn collect_variant_attributes(
                 }
                 variant_tag = Some(tag);
             }
+            VariantAttribute::Other { key_token } => {
+                if other {
+                    let message = "Duplicate 'other'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                other = true;
+            }
         }
     }
 
-    Ok(VariantAttributes { tag: variant_tag })
+    Ok(VariantAttributes {
+        tag: variant_tag,
+        other,
+    })
 }
 
 fn collect_struct_attributes(attrs: Vec<StructAttribute>) -> Result<StructAttributes, syn::Error> {
-    for _attr in attrs {}
+    let mut validate = None;
+    let mut validate_on_write = false;
+    let mut bound = None;
+    let mut extensible = false;
+    for attr in attrs {
+        match attr {
+            StructAttribute::Validate { key_token, path } => {
+                if validate.is_some() {
+                    let message = "Duplicate 'validate'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                validate = Some(path);
+            }
+            StructAttribute::ValidateOnWrite { key_token } => {
+                if validate_on_write {
+                    let message = "Duplicate 'validate_on_write'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                validate_on_write = true;
+            }
+            StructAttribute::Bound {
+                key_token,
+                bound: value,
+            } => {
+                if bound.is_some() {
+                    let message = "Duplicate 'bound'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                bound = Some(value);
+            }
+            StructAttribute::Extensible { key_token } => {
+                if extensible {
+                    let message = "Duplicate 'extensible'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                extensible = true;
+            }
+        }
+    }
 
-    Ok(StructAttributes {})
+    Ok(StructAttributes {
+        validate,
+        validate_on_write,
+        bound: bound.unwrap_or_default(),
+        extensible,
+    })
 }
 
 fn collect_enum_attributes(attrs: Vec<EnumAttribute>) -> Result<EnumAttributes, syn::Error> {
     let mut tag_type = None;
     let mut peek_tag = false;
+    let mut length_delimited = None;
+    let mut validate = None;
+    let mut validate_on_write = false;
+    let mut bound = None;
+    let mut bits = None;
     for attr in attrs {
         match attr {
             EnumAttribute::TagType { key_token, ty } => {
@@ -462,10 +745,63 @@ fn collect_enum_attributes(attrs: Vec<EnumAttribute>) -> Result<EnumAttributes,
                 }
                 peek_tag = true;
             }
+            EnumAttribute::LengthDelimited { key_token } => {
+                if length_delimited.is_some() {
+                    let message = "Duplicate 'length_delimited'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                length_delimited = Some(key_token);
+            }
+            EnumAttribute::Validate { key_token, path } => {
+                if validate.is_some() {
+                    let message = "Duplicate 'validate'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                validate = Some(path);
+            }
+            EnumAttribute::ValidateOnWrite { key_token } => {
+                if validate_on_write {
+                    let message = "Duplicate 'validate_on_write'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                validate_on_write = true;
+            }
+            EnumAttribute::Bound {
+                key_token,
+                bound: value,
+            } => {
+                if bound.is_some() {
+                    let message = "Duplicate 'bound'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                bound = Some(value);
+            }
+            EnumAttribute::Bits { key_token } => {
+                if bits.is_some() {
+                    let message = "Duplicate 'bits'";
+                    return Err(syn::Error::new(key_token.span(), message));
+                }
+                bits = Some(key_token);
+            }
+        }
+    }
+
+    if let Some(key_token) = length_delimited {
+        if peek_tag {
+            let message = "You cannot have both 'peek_tag' and 'length_delimited' on the same enum";
+            return Err(syn::Error::new(key_token.span(), message));
         }
     }
 
-    Ok(EnumAttributes { tag_type, peek_tag })
+    Ok(EnumAttributes {
+        tag_type,
+        peek_tag,
+        length_delimited: length_delimited.is_some(),
+        validate,
+        validate_on_write,
+        bound: bound.unwrap_or_default(),
+        bits,
+    })
 }
 
 #[derive(PartialEq)]
@@ -478,27 +814,37 @@ enum StructKind {
 struct Struct<'a> {
     fields: Vec<Field<'a>>,
     kind: StructKind,
+    validate: Option<syn::Path>,
+    validate_on_write: bool,
+    bound: Bound,
+    extensible: bool,
 }
 
 impl<'a> Struct<'a> {
     fn new(fields: &'a syn::Fields, attrs: Vec<StructAttribute>) -> Result<Self, syn::Error> {
-        collect_struct_attributes(attrs)?;
-        let structure = match fields {
-            syn::Fields::Unit => Struct {
-                fields: Vec::new(),
-                kind: StructKind::Unit,
-            },
-            syn::Fields::Named(syn::FieldsNamed { ref named, .. }) => Struct {
-                fields: get_fields(named.into_iter())?,
-                kind: StructKind::Named,
-            },
-            syn::Fields::Unnamed(syn::FieldsUnnamed { ref unnamed, .. }) => Struct {
-                fields: get_fields(unnamed.into_iter())?,
-                kind: StructKind::Unnamed,
-            },
+        let attrs = collect_struct_attributes(attrs)?;
+        let (fields, kind) = match fields {
+            syn::Fields::Unit => (Vec::new(), StructKind::Unit),
+            syn::Fields::Named(syn::FieldsNamed { ref named, .. }) => {
+                (get_fields(named.into_iter())?, StructKind::Named)
+            }
+            syn::Fields::Unnamed(syn::FieldsUnnamed { ref unnamed, .. }) => {
+                (get_fields(unnamed.into_iter())?, StructKind::Unnamed)
+            }
         };
 
-        Ok(structure)
+        Ok(Struct {
+            fields,
+            kind,
+            validate: attrs.validate,
+            validate_on_write: attrs.validate_on_write,
+            bound: attrs.bound,
+            extensible: attrs.extensible,
+        })
+    }
+
+    fn is_versioned(&self) -> bool {
+        self.fields.iter().any(Field::is_versioned)
     }
 }
 
@@ -512,9 +858,66 @@ struct Field<'a> {
     ty: Opt<Ty>,
     skip: bool,
     constant_prefix: Option<syn::LitByteStr>,
+    encoding: Option<IntegerEncoding>,
+    read_with: Option<syn::Path>,
+    write_with: Option<syn::Path>,
+    minimum_bytes_with: Option<syn::Path>,
+    default: Option<syn::Expr>,
+    validate: Option<syn::Path>,
+    max_length: Option<syn::Expr>,
+    range: Option<syn::Expr>,
+    bound: Bound,
+    since: Option<u32>,
+    until: Option<u32>,
+    bits: Option<u32>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum IntegerEncoding {
+    VarInt,
+    ZigZag,
+}
+
+impl IntegerEncoding {
+    fn attribute_name(self) -> &'static str {
+        match self {
+            IntegerEncoding::VarInt => "varint",
+            IntegerEncoding::ZigZag => "zigzag",
+        }
+    }
+
+    fn allowed_types(self) -> &'static [&'static str] {
+        match self {
+            IntegerEncoding::VarInt => &["u16", "u32", "u64", "u128", "usize"],
+            IntegerEncoding::ZigZag => &["i16", "i32", "i64", "i128", "isize"],
+        }
+    }
+
+    fn wrapper(self) -> TokenStream {
+        match self {
+            IntegerEncoding::VarInt => quote! { persia_speedy::VarInt },
+            IntegerEncoding::ZigZag => quote! { persia_speedy::ZigZag },
+        }
+    }
 }
 
 impl<'a> Field<'a> {
+    fn is_versioned(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    /// Generates a check whether the field is present in the version of the format bound to `_version_`.
+    fn version_check(&self) -> Option<TokenStream> {
+        if !self.is_versioned() {
+            return None;
+        }
+
+        let since = self.since.map(|since| quote! { _version_ >= #since });
+        let until = self.until.map(|until| quote! { _version_ < #until });
+        let conditions = since.into_iter().chain(until);
+        Some(quote! { #(#conditions)&&* })
+    }
+
     fn var_name(&self) -> syn::Ident {
         if let Some(name) = self.name {
             name.clone()
@@ -523,6 +926,14 @@ impl<'a> Field<'a> {
         }
     }
 
+    fn path_name(&self) -> String {
+        if let Some(name) = self.name {
+            name.unraw().to_string()
+        } else {
+            self.index.to_string()
+        }
+    }
+
     fn name(&self) -> syn::Member {
         if let Some(name) = self.name {
             syn::Member::Named(name.clone())
@@ -534,10 +945,52 @@ impl<'a> Field<'a> {
         }
     }
 
-    fn bound_types(&self) -> Vec<syn::Type> {
+    fn bound_types(&self, trait_variant: Trait) -> Vec<syn::Type> {
+        let with = match trait_variant {
+            Trait::Readable => &self.read_with,
+            Trait::Writable => &self.write_with,
+        };
+
+        // Skipped fields are never read nor written, and fields packed into
+        // bits don't use the `Readable` and `Writable` impls.
+        if with.is_some()
+            || self.skip
+            || self.bits.is_some()
+            || self.bound.get(trait_variant).is_some()
+        {
+            return vec![];
+        }
+
+        // `PhantomData` is always serializable, whatever its type parameter is.
+        if let Ty::Ty(syn::Type::Path(syn::TypePath {
+            qself: None,
+            ref path,
+        })) = self.ty.inner()
+        {
+            if path
+                .segments
+                .last()
+                .map_or(false, |segment| segment.ident == "PhantomData")
+            {
+                return vec![];
+            }
+        }
+
+        if let Some(encoding) = self.encoding {
+            let wrapper = encoding.wrapper();
+            return match self.ty.inner() {
+                Ty::Vec(inner_ty) | Ty::Ty(inner_ty) => {
+                    vec![syn::parse_quote! { #wrapper< #inner_ty > }]
+                }
+                _ => unreachable!(),
+            };
+        }
+
         match self.ty.inner() {
             Ty::Array(inner_ty, ..)
             | Ty::Vec(inner_ty)
+            | Ty::BoxSlice(inner_ty)
+            | Ty::VecDeque(inner_ty)
             | Ty::HashSet(inner_ty)
             | Ty::BTreeSet(inner_ty)
             | Ty::CowHashSet(_, inner_ty)
@@ -547,7 +1000,7 @@ impl<'a> Field<'a> {
             | Ty::BTreeMap(key_ty, value_ty)
             | Ty::CowHashMap(_, key_ty, value_ty)
             | Ty::CowBTreeMap(_, key_ty, value_ty) => vec![key_ty.clone(), value_ty.clone()],
-            Ty::String | Ty::CowStr(..) => vec![],
+            Ty::String | Ty::BoxStr | Ty::CowStr(..) => vec![],
             Ty::Ty(_) => vec![self.raw_ty.clone()],
         }
     }
@@ -572,6 +1025,59 @@ enum FieldAttribute {
         key_span: Span,
         prefix: syn::LitByteStr,
     },
+    VarInt {
+        key_span: Span,
+    },
+    ZigZag {
+        key_span: Span,
+    },
+    With {
+        key_span: Span,
+        path: syn::Path,
+    },
+    ReadWith {
+        key_span: Span,
+        path: syn::Path,
+    },
+    WriteWith {
+        key_span: Span,
+        path: syn::Path,
+    },
+    MinimumBytes {
+        key_span: Span,
+    },
+    Default {
+        key_span: Span,
+        expr: syn::Expr,
+    },
+    Validate {
+        key_span: Span,
+        path: syn::Path,
+    },
+    MaxLength {
+        key_span: Span,
+        expr: syn::Expr,
+    },
+    Range {
+        key_span: Span,
+        expr: syn::Expr,
+    },
+    Bound {
+        key_span: Span,
+        bound: Bound,
+    },
+    Since {
+        key_span: Span,
+        version: u32,
+    },
+    Until {
+        key_span: Span,
+        version: u32,
+    },
+    Bits {
+        key_span: Span,
+        count: u32,
+    },
 }
 
 impl syn::parse::Parse for FieldAttribute {
@@ -668,6 +1174,122 @@ impl syn::parse::Parse for FieldAttribute {
                 key_span: key_token.span(),
                 prefix: syn::LitByteStr::new(&prefix, value_span),
             }
+        } else if lookahead.peek(kw::varint) {
+            let key_token = input.parse::<kw::varint>()?;
+            FieldAttribute::VarInt {
+                key_span: key_token.span(),
+            }
+        } else if lookahead.peek(kw::zigzag) {
+            let key_token = input.parse::<kw::zigzag>()?;
+            FieldAttribute::ZigZag {
+                key_span: key_token.span(),
+            }
+        } else if lookahead.peek(kw::with) {
+            let key_token = input.parse::<kw::with>()?;
+            let _: Token![=] = input.parse()?;
+            let path: syn::Path = input.parse()?;
+            FieldAttribute::With {
+                key_span: key_token.span(),
+                path,
+            }
+        } else if lookahead.peek(kw::read_with) {
+            let key_token = input.parse::<kw::read_with>()?;
+            let _: Token![=] = input.parse()?;
+            let path: syn::Path = input.parse()?;
+            FieldAttribute::ReadWith {
+                key_span: key_token.span(),
+                path,
+            }
+        } else if lookahead.peek(kw::write_with) {
+            let key_token = input.parse::<kw::write_with>()?;
+            let _: Token![=] = input.parse()?;
+            let path: syn::Path = input.parse()?;
+            FieldAttribute::WriteWith {
+                key_span: key_token.span(),
+                path,
+            }
+        } else if lookahead.peek(kw::minimum_bytes) {
+            let key_token = input.parse::<kw::minimum_bytes>()?;
+            FieldAttribute::MinimumBytes {
+                key_span: key_token.span(),
+            }
+        } else if lookahead.peek(kw::default) {
+            let key_token = input.parse::<kw::default>()?;
+            let _: Token![=] = input.parse()?;
+            let expr: syn::Expr = input.parse()?;
+            FieldAttribute::Default {
+                key_span: key_token.span(),
+                expr,
+            }
+        } else if lookahead.peek(kw::validate) {
+            let key_token = input.parse::<kw::validate>()?;
+            let _: Token![=] = input.parse()?;
+            let path: syn::Path = input.parse()?;
+            FieldAttribute::Validate {
+                key_span: key_token.span(),
+                path,
+            }
+        } else if lookahead.peek(kw::max_length) {
+            let key_token = input.parse::<kw::max_length>()?;
+            let _: Token![=] = input.parse()?;
+            let expr: syn::Expr = input.parse()?;
+            FieldAttribute::MaxLength {
+                key_span: key_token.span(),
+                expr,
+            }
+        } else if lookahead.peek(kw::range) {
+            let key_token = input.parse::<kw::range>()?;
+            let _: Token![=] = input.parse()?;
+            let expr: syn::Expr = input.parse()?;
+            FieldAttribute::Range {
+                key_span: key_token.span(),
+                expr,
+            }
+        } else if lookahead.peek(kw::bound) {
+            let key_token = input.parse::<kw::bound>()?;
+            let bound = parse_bound(&input)?;
+            FieldAttribute::Bound {
+                key_span: key_token.span(),
+                bound,
+            }
+        } else if lookahead.peek(kw::since) {
+            let key_token = input.parse::<kw::since>()?;
+            let _: Token![=] = input.parse()?;
+            let raw_version: syn::LitInt = input.parse()?;
+            let version = raw_version
+                .base10_parse::<u32>()
+                .map_err(|err| syn::Error::new(raw_version.span(), err))?;
+            FieldAttribute::Since {
+                key_span: key_token.span(),
+                version,
+            }
+        } else if lookahead.peek(kw::until) {
+            let key_token = input.parse::<kw::until>()?;
+            let _: Token![=] = input.parse()?;
+            let raw_version: syn::LitInt = input.parse()?;
+            let version = raw_version
+                .base10_parse::<u32>()
+                .map_err(|err| syn::Error::new(raw_version.span(), err))?;
+            FieldAttribute::Until {
+                key_span: key_token.span(),
+                version,
+            }
+        } else if lookahead.peek(kw::bits) {
+            let key_token = input.parse::<kw::bits>()?;
+            let _: Token![=] = input.parse()?;
+            let raw_count: syn::LitInt = input.parse()?;
+            let count = raw_count
+                .base10_parse::<u32>()
+                .map_err(|err| syn::Error::new(raw_count.span(), err))?;
+            if count == 0 || count > 64 {
+                let message = "The number of bits has to be between 1 and 64";
+                return Err(syn::Error::new(raw_count.span(), message));
+            }
+
+            FieldAttribute::Bits {
+                key_span: key_token.span(),
+                count,
+            }
         } else {
             return Err(lookahead.error());
         };
@@ -693,6 +1315,9 @@ impl<T> Opt<T> {
 enum Ty {
     String,
     Vec(syn::Type),
+    BoxSlice(syn::Type),
+    BoxStr,
+    VecDeque(syn::Type),
     CowSlice(syn::Lifetime, syn::Type),
     CowStr(syn::Lifetime),
     HashMap(syn::Type, syn::Type),
@@ -838,6 +1463,17 @@ fn parse_special_ty(ty: &syn::Type) -> Option<Ty> {
                 }) => {
                     if name == "Vec" {
                         Some(Ty::Vec(extract_inner_ty(args)?.clone()))
+                    } else if name == "VecDeque" {
+                        Some(Ty::VecDeque(extract_inner_ty(args)?.clone()))
+                    } else if name == "Box" {
+                        let ty = extract_inner_ty(args)?;
+                        if let Some(inner_ty) = extract_slice_inner_ty(ty) {
+                            Some(Ty::BoxSlice(inner_ty.clone()))
+                        } else if is_bare_ty(ty, "str") {
+                            Some(Ty::BoxStr)
+                        } else {
+                            None
+                        }
                     } else if name == "HashSet" {
                         Some(Ty::HashSet(extract_inner_ty(args)?.clone()))
                     } else if name == "BTreeSet" {
@@ -944,6 +1580,19 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
             let mut length_type = None;
             let mut skip = false;
             let mut constant_prefix = None;
+            let mut encoding = None;
+            let mut with = None;
+            let mut read_with = None;
+            let mut write_with = None;
+            let mut minimum_bytes = None;
+            let mut default = None;
+            let mut validate = None;
+            let mut max_length = None;
+            let mut range = None;
+            let mut bound = None;
+            let mut since = None;
+            let mut until = None;
+            let mut bits = None;
             for attr in parse_attributes::< FieldAttribute >( &field.attrs )? {
                 match attr {
                     FieldAttribute::DefaultOnEof { key_span } => {
@@ -980,45 +1629,200 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
                         }
                         constant_prefix = Some( prefix );
                     }
-                }
-            }
-
-            if let Some( ref value ) = constant_prefix {
-                if value.value().is_empty() {
-                    constant_prefix = None;
-                }
-            }
-
-            if length_type.is_some() && length.is_some() {
-                let (key_span, _) = length_type.unwrap();
-                let message = "You cannot have both 'length_type' and 'length' on the same field";
-                return Err( syn::Error::new( key_span, message ) );
-            }
-
-            let ty = if let Some( ty ) = extract_option_inner_ty( &field.ty ) {
-                Opt::Option( parse_ty( ty ) )
-            } else {
-                Opt::Plain( parse_ty( &field.ty ) )
-            };
+                    FieldAttribute::VarInt { key_span } | FieldAttribute::ZigZag { key_span } => {
+                        let kind = match attr {
+                            FieldAttribute::VarInt { .. } => IntegerEncoding::VarInt,
+                            _ => IntegerEncoding::ZigZag
+                        };
 
-            if length.is_some() {
-                match ty {
-                    | Opt::Plain( Ty::String )
-                    | Opt::Plain( Ty::Vec( .. ) )
-                    | Opt::Plain( Ty::CowSlice( .. ) )
-                    | Opt::Plain( Ty::CowStr( .. ) )
-                    | Opt::Plain( Ty::HashMap( .. ) )
-                    | Opt::Plain( Ty::HashSet( .. ) )
-                    | Opt::Plain( Ty::BTreeMap( .. ) )
-                    | Opt::Plain( Ty::BTreeSet( .. ) )
-                    | Opt::Plain( Ty::CowHashMap( .. ) )
-                    | Opt::Plain( Ty::CowHashSet( .. ) )
-                    | Opt::Plain( Ty::CowBTreeMap( .. ) )
-                    | Opt::Plain( Ty::CowBTreeSet( .. ) )
-                        => {},
+                        match encoding {
+                            Some( (_, previous) ) if previous == kind => {
+                                let message = format!( "Duplicate '{}'", kind.attribute_name() );
+                                return Err( syn::Error::new( key_span, message ) );
+                            },
+                            Some( _ ) => {
+                                let message = "You cannot have both 'varint' and 'zigzag' on the same field";
+                                return Err( syn::Error::new( key_span, message ) );
+                            },
+                            None => {}
+                        }
 
-                    | Opt::Option( Ty::String )
-                    | Opt::Option( Ty::Vec( .. ) )
+                        encoding = Some( (key_span, kind) );
+                    }
+                    FieldAttribute::With { key_span, path } => {
+                        if with.is_some() {
+                            let message = "Duplicate 'with'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        with = Some( (key_span, path) );
+                    }
+                    FieldAttribute::ReadWith { key_span, path } => {
+                        if read_with.is_some() {
+                            let message = "Duplicate 'read_with'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        read_with = Some( (key_span, path) );
+                    }
+                    FieldAttribute::WriteWith { key_span, path } => {
+                        if write_with.is_some() {
+                            let message = "Duplicate 'write_with'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        write_with = Some( (key_span, path) );
+                    }
+                    FieldAttribute::MinimumBytes { key_span } => {
+                        if minimum_bytes.is_some() {
+                            let message = "Duplicate 'minimum_bytes'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        minimum_bytes = Some( key_span );
+                    }
+                    FieldAttribute::Default { key_span, expr } => {
+                        if default.is_some() {
+                            let message = "Duplicate 'default'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        default = Some( (key_span, expr) );
+                    }
+                    FieldAttribute::Validate { key_span, path } => {
+                        if validate.is_some() {
+                            let message = "Duplicate 'validate'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        validate = Some( (key_span, path) );
+                    }
+                    FieldAttribute::MaxLength { key_span, expr } => {
+                        if max_length.is_some() {
+                            let message = "Duplicate 'max_length'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        max_length = Some( (key_span, expr) );
+                    }
+                    FieldAttribute::Range { key_span, expr } => {
+                        if range.is_some() {
+                            let message = "Duplicate 'range'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        range = Some( (key_span, expr) );
+                    }
+                    FieldAttribute::Bound { key_span, bound: value } => {
+                        if bound.is_some() {
+                            let message = "Duplicate 'bound'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        bound = Some( value );
+                    }
+                    FieldAttribute::Since { key_span, version } => {
+                        if since.is_some() {
+                            let message = "Duplicate 'since'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        since = Some( (key_span, version) );
+                    }
+                    FieldAttribute::Until { key_span, version } => {
+                        if until.is_some() {
+                            let message = "Duplicate 'until'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        until = Some( (key_span, version) );
+                    }
+                    FieldAttribute::Bits { key_span, count } => {
+                        if bits.is_some() {
+                            let message = "Duplicate 'bits'";
+                            return Err( syn::Error::new( key_span, message ) );
+                        }
+                        bits = Some( (key_span, count) );
+                    }
+                }
+            }
+
+            if let Some( (key_span, _) ) = since.or( until ) {
+                if skip {
+                    let message = "You cannot combine 'since' or 'until' with 'skip'";
+                    return Err( syn::Error::new( key_span, message ) );
+                }
+            }
+
+            if let (Some( (_, since_version) ), Some( (key_span, until_version) )) = (since, until) {
+                if since_version >= until_version {
+                    let message = "The version in 'until' has to be greater than the one in 'since'";
+                    return Err( syn::Error::new( key_span, message ) );
+                }
+            }
+
+            if let Some( (key_span, _) ) = default {
+                if !skip && default_on_eof.is_none() && since.is_none() && until.is_none() {
+                    let message = "The 'default' attribute can only be used together with 'skip', 'default_on_eof', 'since' or 'until'";
+                    return Err( syn::Error::new( key_span, message ) );
+                }
+            }
+
+            let mut minimum_bytes_with = None;
+            if let Some( (key_span, path) ) = with {
+                if read_with.is_some() || write_with.is_some() {
+                    let message = "You cannot have 'with' together with either 'read_with' or 'write_with' on the same field";
+                    return Err( syn::Error::new( key_span, message ) );
+                }
+
+                read_with = Some( (key_span, syn::parse_quote! { #path::read }) );
+                write_with = Some( (key_span, syn::parse_quote! { #path::write }) );
+                if minimum_bytes.is_some() {
+                    minimum_bytes_with = Some( syn::parse_quote! { #path::minimum_bytes } );
+                }
+            } else if let Some( key_span ) = minimum_bytes {
+                let message = "The 'minimum_bytes' attribute can only be used together with 'with'";
+                return Err( syn::Error::new( key_span, message ) );
+            }
+
+            if let Some( (key_span, _) ) = read_with.as_ref().or( write_with.as_ref() ) {
+                if length.is_some() || length_type.is_some() || encoding.is_some() || max_length.is_some() {
+                    let message = "You cannot combine 'length', 'length_type', 'max_length', 'varint' or 'zigzag' with a custom read or write function";
+                    return Err( syn::Error::new( *key_span, message ) );
+                }
+            }
+
+            if let Some( ref value ) = constant_prefix {
+                if value.value().is_empty() {
+                    constant_prefix = None;
+                }
+            }
+
+            if length_type.is_some() && length.is_some() {
+                let (key_span, _) = length_type.unwrap();
+                let message = "You cannot have both 'length_type' and 'length' on the same field";
+                return Err( syn::Error::new( key_span, message ) );
+            }
+
+            let ty = if let Some( ty ) = extract_option_inner_ty( &field.ty ) {
+                Opt::Option( parse_ty( ty ) )
+            } else {
+                Opt::Plain( parse_ty( &field.ty ) )
+            };
+
+            if length.is_some() {
+                match ty {
+                    | Opt::Plain( Ty::String )
+                    | Opt::Plain( Ty::Vec( .. ) )
+                    | Opt::Plain( Ty::BoxSlice( .. ) )
+                    | Opt::Plain( Ty::BoxStr )
+                    | Opt::Plain( Ty::VecDeque( .. ) )
+                    | Opt::Plain( Ty::CowSlice( .. ) )
+                    | Opt::Plain( Ty::CowStr( .. ) )
+                    | Opt::Plain( Ty::HashMap( .. ) )
+                    | Opt::Plain( Ty::HashSet( .. ) )
+                    | Opt::Plain( Ty::BTreeMap( .. ) )
+                    | Opt::Plain( Ty::BTreeSet( .. ) )
+                    | Opt::Plain( Ty::CowHashMap( .. ) )
+                    | Opt::Plain( Ty::CowHashSet( .. ) )
+                    | Opt::Plain( Ty::CowBTreeMap( .. ) )
+                    | Opt::Plain( Ty::CowBTreeSet( .. ) )
+                        => {},
+
+                    | Opt::Option( Ty::String )
+                    | Opt::Option( Ty::Vec( .. ) )
+                    | Opt::Option( Ty::BoxSlice( .. ) )
+                    | Opt::Option( Ty::BoxStr )
+                    | Opt::Option( Ty::VecDeque( .. ) )
                     | Opt::Option( Ty::CowSlice( .. ) )
                     | Opt::Option( Ty::CowStr( .. ) )
                     | Opt::Option( Ty::HashMap( .. ) )
@@ -1037,7 +1841,7 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
                         return Err(
                             syn::Error::new(
                                 field.ty.span(),
-                                "The 'length' attribute is only supported for `Vec`, `String`, `Cow<[_]>`, `Cow<str>`, `HashMap`, `HashSet`, `BTreeMap`, `BTreeSet`, `Cow<HashMap>`, `Cow<HashSet>`, `Cow<BTreeMap>`, `Cow<BTreeSet>`"
+                                "The 'length' attribute is only supported for `Vec`, `String`, `Box<[_]>`, `Box<str>`, `VecDeque`, `Cow<[_]>`, `Cow<str>`, `HashMap`, `HashSet`, `BTreeMap`, `BTreeSet`, `Cow<HashMap>`, `Cow<HashSet>`, `Cow<BTreeMap>`, `Cow<BTreeSet>`"
                             )
                         );
                     }
@@ -1048,6 +1852,9 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
                 match ty {
                     | Opt::Plain( Ty::String )
                     | Opt::Plain( Ty::Vec( .. ) )
+                    | Opt::Plain( Ty::BoxSlice( .. ) )
+                    | Opt::Plain( Ty::BoxStr )
+                    | Opt::Plain( Ty::VecDeque( .. ) )
                     | Opt::Plain( Ty::CowSlice( .. ) )
                     | Opt::Plain( Ty::CowStr( .. ) )
                     | Opt::Plain( Ty::HashMap( .. ) )
@@ -1060,6 +1867,9 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
                     | Opt::Plain( Ty::CowBTreeSet( .. ) )
                     | Opt::Option( Ty::String )
                     | Opt::Option( Ty::Vec( .. ) )
+                    | Opt::Option( Ty::BoxSlice( .. ) )
+                    | Opt::Option( Ty::BoxStr )
+                    | Opt::Option( Ty::VecDeque( .. ) )
                     | Opt::Option( Ty::CowSlice( .. ) )
                     | Opt::Option( Ty::CowStr( .. ) )
                     | Opt::Option( Ty::HashMap( .. ) )
@@ -1080,13 +1890,99 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
                         return Err(
                             syn::Error::new(
                                 field.ty.span(),
-                                "The 'length_type' attribute is only supported for `Vec`, `String`, `Cow<[_]>`, `Cow<str>`, `HashMap`, `HashSet`, `BTreeMap`, `BTreeSet`, `Cow<HashMap>`, `Cow<HashSet>`, `Cow<BTreeMap>`, `Cow<BTreeSet>` and for `Option<T>` where `T` is one of these types"
+                                "The 'length_type' attribute is only supported for `Vec`, `String`, `Box<[_]>`, `Box<str>`, `VecDeque`, `Cow<[_]>`, `Cow<str>`, `HashMap`, `HashSet`, `BTreeMap`, `BTreeSet`, `Cow<HashMap>`, `Cow<HashSet>`, `Cow<BTreeMap>`, `Cow<BTreeSet>` and for `Option<T>` where `T` is one of these types"
                             )
                         );
                     }
                 }
             }
 
+            if let Some( (key_span, encoding) ) = encoding {
+                let name = encoding.attribute_name();
+                let inner_ty = match ty.inner() {
+                    Ty::Ty( inner_ty ) | Ty::Vec( inner_ty ) => inner_ty,
+                    _ => {
+                        let message = format!( "The '{}' attribute is only supported for integers and for `Vec<T>` and `Option<T>` of integers", name );
+                        return Err( syn::Error::new( key_span, message ) );
+                    }
+                };
+
+                if let syn::Type::Path( syn::TypePath { qself: None, ref path } ) = *inner_ty {
+                    if let Some( ident ) = path.get_ident() {
+                        let ident = ident.to_string();
+                        let is_primitive = PRIMITIVE_TYPES.contains( &ident.as_str() );
+                        if is_primitive && !encoding.allowed_types().contains( &ident.as_str() ) {
+                            let message = format!( "The '{}' attribute is only supported for {}", name, encoding.allowed_types().join( ", " ) );
+                            return Err( syn::Error::new( inner_ty.span(), message ) );
+                        }
+                    }
+                }
+            }
+
+            if let Some( (key_span, _) ) = max_length {
+                match ty.inner() {
+                    Ty::Array( .. ) | Ty::Ty( .. ) => {
+                        let message = "The 'max_length' attribute is only supported for containers which have a length";
+                        return Err( syn::Error::new( key_span, message ) );
+                    },
+                    _ => {}
+                }
+            }
+
+            if let Some( (key_span, _) ) = range {
+                match ty.inner() {
+                    Ty::Ty( .. ) => {},
+                    _ => {
+                        let message = "The 'range' attribute is only supported for numbers and for `Option<T>` of numbers";
+                        return Err( syn::Error::new( key_span, message ) );
+                    }
+                }
+            }
+
+            if let Some( (key_span, count) ) = bits {
+                if skip || default_on_eof.is_some() || length.is_some() || length_type.is_some() || constant_prefix.is_some() ||
+                    encoding.is_some() || read_with.is_some() || write_with.is_some() || max_length.is_some() ||
+                    since.is_some() || until.is_some()
+                {
+                    let message = "The 'bits' attribute can only be combined with 'range', 'validate' and 'bound'";
+                    return Err( syn::Error::new( key_span, message ) );
+                }
+
+                let inner_ty = match ty {
+                    Opt::Plain( Ty::Ty( ref inner_ty ) ) => inner_ty,
+                    _ => {
+                        let message = "The 'bits' attribute is only supported for integers, `bool` and C-like enums";
+                        return Err( syn::Error::new( key_span, message ) );
+                    }
+                };
+
+                if let syn::Type::Path( syn::TypePath { qself: None, ref path } ) = *inner_ty {
+                    if let Some( ident ) = path.get_ident() {
+                        let ident = ident.to_string();
+                        let width = match ident.as_str() {
+                            "bool" => Some( 1 ),
+                            "u8" | "i8" => Some( 8 ),
+                            "u16" | "i16" => Some( 16 ),
+                            "u32" | "i32" => Some( 32 ),
+                            "u64" | "i64" | "usize" | "isize" => Some( 64 ),
+                            _ => None
+                        };
+
+                        match width {
+                            Some( width ) if count > width => {
+                                let message = format!( "The '{}' type cannot have more than {} bits", ident, width );
+                                return Err( syn::Error::new( key_span, message ) );
+                            },
+                            None if PRIMITIVE_TYPES.contains( &ident.as_str() ) => {
+                                let message = "The 'bits' attribute is only supported for integers, `bool` and C-like enums";
+                                return Err( syn::Error::new( inner_ty.span(), message ) );
+                            },
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
             fn snd< T, U >( (_, b): (T, U) ) -> U {
                 b
             }
@@ -1100,28 +1996,72 @@ fn get_fields<'a, I: IntoIterator<Item = &'a syn::Field> + 'a>(
                 length_type: length_type.map( snd ),
                 ty,
                 skip,
-                constant_prefix
+                constant_prefix,
+                encoding: encoding.map( snd ),
+                read_with: read_with.map( snd ),
+                write_with: write_with.map( snd ),
+                minimum_bytes_with,
+                default: default.map( snd ),
+                validate: validate.map( snd ),
+                max_length: max_length.map( snd ),
+                range: range.map( snd ),
+                bound: bound.unwrap_or_default(),
+                since: since.map( snd ),
+                until: until.map( snd ),
+                bits: bits.map( snd )
             })
         });
 
     iter.collect()
 }
 
-fn default_on_eof_body(body: TokenStream) -> TokenStream {
+fn default_on_eof_body(body: TokenStream, on_eof: TokenStream) -> TokenStream {
     quote! {
         match #body {
             Ok( value ) => value,
-            Err( ref error ) if persia_speedy::IsEof::is_eof( error ) => std::default::Default::default(),
+            Err( ref error ) if persia_speedy::IsEof::is_eof( error ) => #on_eof,
             Err( error ) => return Err( error )
         }
     }
 }
 
+fn default_value_body(field: &Field) -> TokenStream {
+    match field.default {
+        Some(ref default) => quote! { (#default) },
+        None => quote! { ::core::default::Default::default() },
+    }
+}
+
+/// Generates the checks for the `range` and `validate` attributes;
+/// `value` has to evaluate to a reference to the field's value.
+fn field_checks_body(field: &Field, value: TokenStream) -> TokenStream {
+    let field_name = field.path_name();
+    let mut checks = Vec::new();
+    if let Some(ref range) = field.range {
+        let check = quote! {
+            if !persia_speedy::private::is_in_range( #range, _value_ ) {
+                return Err( persia_speedy::private::error_value_is_not_in_range_attribute( #field_name ) );
+            }
+        };
+
+        checks.push(match field.ty {
+            Opt::Plain(_) => quote! {{ let _value_ = #value; #check }},
+            Opt::Option(_) => quote! { if let Some( _value_ ) = #value { #check } },
+        });
+    }
+
+    if let Some(ref validate) = field.validate {
+        checks.push(quote! {
+            persia_speedy::private::validate( Some( #field_name ), #validate( #value ) )?;
+        });
+    }
+
+    quote! { #(#checks)* }
+}
+
 fn read_field_body(field: &Field) -> TokenStream {
     if field.skip {
-        return quote! {
-            std::default::Default::default()
-        };
+        return default_value_body(field);
     }
 
     let read_length_body = match field.length {
@@ -1140,14 +2080,31 @@ fn read_field_body(field: &Field) -> TokenStream {
                 persia_speedy::private::#read_length_fn( _reader_ )
             };
 
-            if field.default_on_eof {
-                default_on_eof_body(body)
+            if field.default_on_eof && field.default.is_none() {
+                default_on_eof_body(body, quote! { ::core::default::Default::default() })
             } else {
+                // If there's a custom default then it has to be used for the whole field
+                // instead of just pretending that the container is empty, so the error
+                // is passed on to where the whole field is defaulted.
                 quote! { #body? }
             }
         }
     };
 
+    let read_length_body = match field.max_length {
+        Some(ref max_length) => {
+            let field_name = field.path_name();
+            quote! {{
+                let _length_ = #read_length_body;
+                if _length_ > ((#max_length) as usize) {
+                    return Err( persia_speedy::private::error_length_is_above_max_length_attribute( #field_name, _length_, (#max_length) as usize ) );
+                }
+                _length_
+            }}
+        }
+        None => read_length_body,
+    };
+
     let read_string = || {
         quote! {{
             let _length_ = #read_length_body;
@@ -1162,6 +2119,29 @@ fn read_field_body(field: &Field) -> TokenStream {
         }}
     };
 
+    let read_box_slice = || {
+        quote! {{
+            let _length_ = #read_length_body;
+            _reader_.read_vec( _length_ ).map( |vec| vec.into_boxed_slice() )
+        }}
+    };
+
+    let read_box_str = || {
+        quote! {{
+            let _length_ = #read_length_body;
+            _reader_.read_vec( _length_ )
+                .and_then( persia_speedy::private::vec_to_string )
+                .map( |string| string.into_boxed_str() )
+        }}
+    };
+
+    let read_vec_deque = || {
+        quote! {{
+            let _length_ = #read_length_body;
+            _reader_.read_vec( _length_ ).map( persia_speedy::private::VecDeque::from )
+        }}
+    };
+
     let read_cow_slice = || {
         quote! {{
             let _length_ = #read_length_body;
@@ -1186,24 +2166,28 @@ fn read_field_body(field: &Field) -> TokenStream {
     let read_cow_collection = || {
         quote! {{
             let _length_ = #read_length_body;
-            _reader_.read_collection( _length_ ).map( std::borrow::Cow::Owned )
+            _reader_.read_collection( _length_ ).map( persia_speedy::private::Cow::Owned )
         }}
     };
 
-    let read_array = |length: u32| {
-        // TODO: This is quite inefficient; for primitive types we can do better.
-        let readers = (0..length).map(|_| {
-            quote! {
-                match _reader_.read_value() {
-                    Ok( value ) => value,
-                    Err( error ) => return Err( error )
-                }
-            }
-        });
+    let read_array = || {
+        quote! { _reader_.read_value() }
+    };
 
-        quote! { (|| { Ok([
-            #(#readers),*
-        ])})() }
+    let read_encoded_value = |encoding: IntegerEncoding, ty: &syn::Type| {
+        let wrapper = encoding.wrapper();
+        quote! {
+            _reader_.read_value::< #wrapper< #ty > >().map( |_value_| _value_.0 )
+        }
+    };
+
+    let read_encoded_vec = |encoding: IntegerEncoding, ty: &syn::Type| {
+        let wrapper = encoding.wrapper();
+        quote! {{
+            let _length_ = #read_length_body;
+            _reader_.read_vec::< #wrapper< #ty > >( _length_ )
+                .map( |_vec_| _vec_.into_iter().map( |_value_| _value_.0 ).collect() )
+        }}
     };
 
     let read_option = |tokens: TokenStream| {
@@ -1219,8 +2203,17 @@ fn read_field_body(field: &Field) -> TokenStream {
     };
 
     let body = match field.ty.inner() {
+        _ if field.read_with.is_some() => {
+            let read_with = field.read_with.as_ref().unwrap();
+            quote! { #read_with( _reader_ ) }
+        }
+        Ty::Vec(ty) if field.encoding.is_some() => read_encoded_vec(field.encoding.unwrap(), ty),
+        Ty::Ty(ty) if field.encoding.is_some() => read_encoded_value(field.encoding.unwrap(), ty),
         Ty::String => read_string(),
         Ty::Vec(..) => read_vec(),
+        Ty::BoxSlice(..) => read_box_slice(),
+        Ty::BoxStr => read_box_str(),
+        Ty::VecDeque(..) => read_vec_deque(),
         Ty::CowSlice(..) => read_cow_slice(),
         Ty::CowStr(..) => read_cow_str(),
         Ty::HashMap(..) | Ty::HashSet(..) | Ty::BTreeMap(..) | Ty::BTreeSet(..) => {
@@ -1229,7 +2222,7 @@ fn read_field_body(field: &Field) -> TokenStream {
         Ty::CowHashMap(..) | Ty::CowHashSet(..) | Ty::CowBTreeMap(..) | Ty::CowBTreeSet(..) => {
             read_cow_collection()
         }
-        Ty::Array(_, length) => read_array(*length),
+        Ty::Array(..) => read_array(),
         Ty::Ty(..) => {
             assert!(field.length.is_none());
             quote! { _reader_.read_value() }
@@ -1237,8 +2230,8 @@ fn read_field_body(field: &Field) -> TokenStream {
     };
 
     let body = match field.ty {
-        Opt::Plain(_) => body,
-        Opt::Option(_) => read_option(body),
+        Opt::Option(_) if field.read_with.is_none() => read_option(body),
+        _ => body,
     };
 
     let body = if let Some(ref constant_prefix) = field.constant_prefix {
@@ -1250,32 +2243,125 @@ fn read_field_body(field: &Field) -> TokenStream {
     };
 
     if field.default_on_eof {
-        default_on_eof_body(body)
+        // The closure keeps an error from the length from returning early.
+        let body = if field.default.is_some() {
+            quote! { (|| #body)() }
+        } else {
+            body
+        };
+
+        default_on_eof_body(body, default_value_body(field))
     } else {
         quote! { #body? }
     }
 }
 
+fn bits_to_bytes(bits: u32) -> TokenStream {
+    let bytes = ((bits + 7) / 8) as usize;
+    quote! { #bytes }
+}
+
 fn readable_body<'a>(
     types: &mut Vec<syn::Type>,
+    predicates: &mut Vec<syn::WherePredicate>,
     st: &Struct<'a>,
+    type_name: &str,
+    variant_name: Option<&syn::Ident>,
 ) -> (TokenStream, TokenStream, TokenStream) {
     let mut field_names = Vec::new();
     let mut field_readers = Vec::new();
     let mut minimum_bytes_needed = Vec::new();
+    let mut bits_in_group = None;
     for field in &st.fields {
-        let read_value = read_field_body(field);
         let name = field.var_name();
         let raw_ty = field.raw_ty;
-        field_readers.push(quote! { let #name: #raw_ty = #read_value; });
+        let field_path = match variant_name {
+            Some(variant_name) => format!("{}.{}", variant_name.unraw(), field.path_name()),
+            None => field.path_name(),
+        };
+
+        if let Some(count) = field.bits {
+            // Consecutive fields with the 'bits' attribute are packed together.
+            if bits_in_group.is_none() {
+                field_readers.push(quote! {
+                    let mut _bits_ = persia_speedy::BitReader::new( _reader_ );
+                });
+            }
+
+            *bits_in_group.get_or_insert(0) += count;
+            let checks = field_checks_body(field, quote! { &_value_ });
+            field_readers.push(quote! {
+                let #name: #raw_ty = {
+                    let _value_: #raw_ty = persia_speedy::private::read_bits_field( &mut _bits_, #count )
+                        .map_err( |error| persia_speedy::private::error_add_field( error, #type_name, #field_path ) )?;
+                    #checks
+                    _value_
+                };
+            });
+
+            if field.bound.read.is_none() {
+                predicates.push(syn::parse_quote! { #raw_ty: persia_speedy::ReadableBits });
+            }
+        } else {
+            if let Some(bits) = bits_in_group.take() {
+                minimum_bytes_needed.push(bits_to_bytes(bits));
+            }
+
+            let read_value = read_field_body(field);
+            if field.skip {
+                field_readers.push(quote! { let #name: #raw_ty = #read_value; });
+            } else {
+                let checks = field_checks_body(field, quote! { &_value_ });
+                let read_value = if checks.is_empty() {
+                    quote! { Ok( #read_value ) }
+                } else {
+                    quote! {{
+                        let _value_: #raw_ty = #read_value;
+                        #checks
+                        Ok( _value_ )
+                    }}
+                };
+
+                let read_value = quote! {
+                    persia_speedy::private::read_field( _reader_, #type_name, #field_path, |_reader_| #read_value )?
+                };
+
+                let read_value = match field.version_check() {
+                    Some(check) => {
+                        let default = default_value_body(field);
+                        quote! {
+                            if {
+                                let _version_: u32 = persia_speedy::Versioned::version( _reader_.context() );
+                                #check
+                            } {
+                                #read_value
+                            } else {
+                                #default
+                            }
+                        }
+                    }
+                    None => read_value,
+                };
+
+                field_readers.push(quote! {
+                    let #name: #raw_ty = #read_value;
+                });
+            }
+        }
+
         field_names.push(name);
-        types.extend(field.bound_types());
+        types.extend(field.bound_types(Trait::Readable));
+        predicates.extend(field.bound.read.iter().flatten().cloned());
 
         if let Some(minimum_bytes) = get_minimum_bytes(&field) {
             minimum_bytes_needed.push(minimum_bytes);
         }
     }
 
+    if let Some(bits) = bits_in_group {
+        minimum_bytes_needed.push(bits_to_bytes(bits));
+    }
+
     let body = quote! { #(#field_readers)* };
     let initializer = quote! { #(#field_names),* };
     let initializer = match st.kind {
@@ -1288,7 +2374,7 @@ fn readable_body<'a>(
     (body, initializer, minimum_bytes_needed)
 }
 
-fn write_field_body(field: &Field) -> TokenStream {
+fn write_field_body(field: &Field, validate_on_write: bool) -> TokenStream {
     let name = field.var_name();
     let write_length_body = match field.length {
         Some(ref length) => {
@@ -1313,6 +2399,19 @@ fn write_field_body(field: &Field) -> TokenStream {
         }
     };
 
+    let write_length_body = match field.max_length {
+        Some(ref max_length) if validate_on_write => {
+            let field_name = field.path_name();
+            quote! {
+                if #name.len() > ((#max_length) as usize) {
+                    return Err( persia_speedy::private::error_length_is_above_max_length_attribute( #field_name, #name.len(), (#max_length) as usize ) );
+                }
+                #write_length_body
+            }
+        }
+        _ => write_length_body,
+    };
+
     let write_str = || {
         quote! {{
             #write_length_body
@@ -1327,6 +2426,15 @@ fn write_field_body(field: &Field) -> TokenStream {
         }}
     };
 
+    let write_vec_deque = || {
+        quote! {{
+            #write_length_body
+            let (_first_, _second_) = #name.as_slices();
+            _writer_.write_slice( _first_ )?;
+            _writer_.write_slice( _second_ )?;
+        }}
+    };
+
     let write_collection = || {
         quote! {{
             #write_length_body
@@ -1340,6 +2448,21 @@ fn write_field_body(field: &Field) -> TokenStream {
         }}
     };
 
+    let write_encoded_value = |encoding: IntegerEncoding| {
+        let wrapper = encoding.wrapper();
+        quote! {{
+            _writer_.write_value( &#wrapper( *#name ) )?;
+        }}
+    };
+
+    let write_encoded_vec = |encoding: IntegerEncoding| {
+        let wrapper = encoding.wrapper();
+        quote! {{
+            #write_length_body
+            _writer_.write_collection( #name.iter().map( |_value_| #wrapper( *_value_ ) ) )?;
+        }}
+    };
+
     let write_option = |tokens: TokenStream| {
         quote! {{
             if let Some( ref #name ) = #name {
@@ -1352,8 +2475,15 @@ fn write_field_body(field: &Field) -> TokenStream {
     };
 
     let body = match field.ty.inner() {
-        Ty::String | Ty::CowStr(..) => write_str(),
-        Ty::Vec(..) | Ty::CowSlice(..) => write_slice(),
+        _ if field.write_with.is_some() => {
+            let write_with = field.write_with.as_ref().unwrap();
+            quote! { #write_with( #name, _writer_ )?; }
+        }
+        Ty::Vec(..) if field.encoding.is_some() => write_encoded_vec(field.encoding.unwrap()),
+        Ty::Ty(..) if field.encoding.is_some() => write_encoded_value(field.encoding.unwrap()),
+        Ty::String | Ty::BoxStr | Ty::CowStr(..) => write_str(),
+        Ty::Vec(..) | Ty::BoxSlice(..) | Ty::CowSlice(..) => write_slice(),
+        Ty::VecDeque(..) => write_vec_deque(),
         Ty::HashMap(..)
         | Ty::HashSet(..)
         | Ty::BTreeMap(..)
@@ -1370,8 +2500,8 @@ fn write_field_body(field: &Field) -> TokenStream {
     };
 
     let body = match field.ty {
-        Opt::Plain(_) => body,
-        Opt::Option(_) => write_option(body),
+        Opt::Option(_) if field.write_with.is_none() => write_option(body),
+        _ => body,
     };
 
     let body = if let Some(ref constant_prefix) = field.constant_prefix {
@@ -1383,24 +2513,87 @@ fn write_field_body(field: &Field) -> TokenStream {
         body
     };
 
-    body
+    if validate_on_write {
+        let checks = field_checks_body(field, quote! { #name });
+        quote! {
+            #checks
+            #body
+        }
+    } else {
+        body
+    }
 }
 
-fn writable_body<'a>(types: &mut Vec<syn::Type>, st: &Struct<'a>) -> (TokenStream, TokenStream) {
+fn writable_body<'a>(
+    types: &mut Vec<syn::Type>,
+    predicates: &mut Vec<syn::WherePredicate>,
+    st: &Struct<'a>,
+    validate_on_write: bool,
+) -> (TokenStream, TokenStream) {
     let mut field_names = Vec::new();
     let mut field_writers = Vec::new();
+    let mut in_bits_group = false;
     for field in &st.fields {
+        if let Some(count) = field.bits {
+            let name = field.var_name();
+            let raw_ty = field.raw_ty;
+            if !in_bits_group {
+                in_bits_group = true;
+                field_writers.push(quote! {
+                    let mut _bits_ = persia_speedy::BitWriter::new( _writer_ );
+                });
+            }
+
+            if validate_on_write {
+                field_writers.push(field_checks_body(field, quote! { #name }));
+            }
+
+            field_writers.push(quote! {
+                persia_speedy::private::write_bits_field( &mut _bits_, #count, #name )?;
+            });
+
+            if field.bound.write.is_none() {
+                predicates.push(syn::parse_quote! { #raw_ty: persia_speedy::WritableBits });
+            }
+
+            field_names.push(name);
+            predicates.extend(field.bound.write.iter().flatten().cloned());
+            continue;
+        }
+
+        if in_bits_group {
+            in_bits_group = false;
+            field_writers.push(quote! { _bits_.flush()?; });
+        }
+
         if field.skip {
             continue;
         }
 
-        let write_value = write_field_body(&field);
-        types.extend(field.bound_types());
+        let write_value = write_field_body(&field, validate_on_write);
+        // Without a version we're only calculating the size, so the field is counted just in case.
+        let write_value = match field.version_check() {
+            Some(check) => quote! {
+                if match persia_speedy::private::writer_version( _writer_ ) {
+                    Some(_version_) => #check,
+                    None => true,
+                } {
+                    #write_value
+                }
+            },
+            None => write_value,
+        };
+        types.extend(field.bound_types(Trait::Writable));
+        predicates.extend(field.bound.write.iter().flatten().cloned());
 
         field_names.push(field.var_name().clone());
         field_writers.push(write_value);
     }
 
+    if in_bits_group {
+        field_writers.push(quote! { _bits_.flush()?; });
+    }
+
     let body = quote! { #(#field_writers)* };
     let initializer = quote! { #(ref #field_names),* };
     let initializer = match st.kind {
@@ -1413,14 +2606,21 @@ fn writable_body<'a>(types: &mut Vec<syn::Type>, st: &Struct<'a>) -> (TokenStrea
 }
 
 struct Variant<'a> {
+    tag: u64,
     tag_expr: TokenStream,
     ident: &'a syn::Ident,
     structure: Struct<'a>,
+    other: bool,
 }
 
 struct Enum<'a> {
     tag_type: BasicType,
     peek_tag: bool,
+    length_delimited: bool,
+    validate: Option<syn::Path>,
+    validate_on_write: bool,
+    bound: Bound,
+    bits: bool,
     variants: Vec<Variant<'a>>,
 }
 
@@ -1444,6 +2644,7 @@ impl<'a> Enum<'a> {
 
         let mut previous_tag = None;
         let mut tag_to_full_name = HashMap::new();
+        let mut other_full_name: Option<String> = None;
         let mut variants = Vec::new();
         for variant in raw_variants {
             let mut struct_attrs = Vec::new();
@@ -1517,7 +2718,7 @@ impl<'a> Enum<'a> {
                 return Err(syn::Error::new(variant.span(), message));
             }
 
-            tag_to_full_name.insert(tag, full_name);
+            tag_to_full_name.insert(tag, full_name.clone());
             let tag_expr = match tag_type {
                 BasicType::U7 | BasicType::U8 => {
                     let tag = tag as u8;
@@ -1538,32 +2739,121 @@ impl<'a> Enum<'a> {
             };
 
             let structure = Struct::new(&variant.fields, struct_attrs)?;
+            if structure.validate.is_some()
+                || structure.validate_on_write
+                || !structure.bound.is_empty()
+            {
+                let message = "The 'validate', 'validate_on_write' and 'bound' attributes can only be put on the enum itself";
+                return Err(syn::Error::new(variant.span(), message));
+            }
+
+            if structure.extensible {
+                let message = "The 'extensible' attribute can only be put on structs";
+                return Err(syn::Error::new(variant.span(), message));
+            }
+
+            if variant_attrs.other {
+                if let Some(other_full_name) = other_full_name {
+                    let message = format!(
+                        "Only one variant can be marked with 'other': `{}`, `{}`",
+                        full_name, other_full_name
+                    );
+                    return Err(syn::Error::new(variant.span(), message));
+                }
+
+                match (&structure.kind, structure.fields.len()) {
+                    (StructKind::Unit, _) => {}
+                    (StructKind::Unnamed, 1) if !attrs.peek_tag => {}
+                    (StructKind::Unnamed, 2) if !attrs.peek_tag => {
+                        if !attrs.length_delimited {
+                            let message = "An 'other' variant which captures the variant's body requires the enum to be 'length_delimited'";
+                            return Err(syn::Error::new(variant.span(), message));
+                        }
+                    }
+                    (StructKind::Unnamed, 1) | (StructKind::Unnamed, 2) => {
+                        let message = "An 'other' variant with fields cannot be used together with 'peek_tag'";
+                        return Err(syn::Error::new(variant.span(), message));
+                    }
+                    _ => {
+                        let message = "An 'other' variant has to be either a unit variant, a `(u64)` tuple variant or a `(u64, Vec<u8>)` tuple variant";
+                        return Err(syn::Error::new(variant.span(), message));
+                    }
+                }
+
+                other_full_name = Some(full_name);
+            }
+
             variants.push(Variant {
+                tag,
                 tag_expr,
                 ident: &variant.ident,
                 structure,
+                other: variant_attrs.other,
             });
         }
 
-        Ok(Enum {
+        let enumeration = Enum {
             tag_type,
             peek_tag: attrs.peek_tag,
+            length_delimited: attrs.length_delimited,
+            validate: attrs.validate,
+            validate_on_write: attrs.validate_on_write,
+            bound: attrs.bound,
+            bits: attrs.bits.is_some(),
             variants,
-        })
+        };
+
+        if let Some(key_token) = attrs.bits {
+            if !enumeration.is_c_like() {
+                let message =
+                    "The 'bits' attribute can only be used on enums with only unit variants";
+                return Err(syn::Error::new(key_token.span(), message));
+            }
+        }
+
+        Ok(enumeration)
+    }
+
+    /// Whether this enum has only unit variants, in which case
+    /// it can be packed into bits using its tags.
+    fn is_c_like(&self) -> bool {
+        !self.variants.is_empty()
+            && self
+                .variants
+                .iter()
+                .all(|variant| variant.structure.kind == StructKind::Unit)
+    }
+
+    fn is_versioned(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| variant.structure.is_versioned())
     }
 }
 
 fn get_minimum_bytes(field: &Field) -> Option<TokenStream> {
-    if field.default_on_eof || field.length.is_some() || field.skip {
+    if field.default_on_eof
+        || field.length.is_some()
+        || field.skip
+        || field.is_versioned()
+        || field.bits.is_some()
+    {
         None
     } else {
         let mut length = match field.ty {
+            _ if field.read_with.is_some() => match field.minimum_bytes_with {
+                Some(ref minimum_bytes_with) => quote! { #minimum_bytes_with() },
+                None => quote! { 0 },
+            },
             Opt::Option(..) => {
                 quote! { 1 }
             }
             Opt::Plain(ref ty) => match ty {
                 Ty::String
                 | Ty::Vec(..)
+                | Ty::BoxSlice(..)
+                | Ty::BoxStr
+                | Ty::VecDeque(..)
                 | Ty::CowSlice(..)
                 | Ty::CowStr(..)
                 | Ty::HashMap(..)
@@ -1587,6 +2877,9 @@ fn get_minimum_bytes(field: &Field) -> Option<TokenStream> {
                     let length = *length as usize;
                     quote! { <#ty as persia_speedy::Readable< 'a_, C_ >>::minimum_bytes_needed() * #length }
                 }
+                Ty::Ty(..) if field.encoding.is_some() => {
+                    quote! { 1 }
+                }
                 Ty::Ty(..) => {
                     let raw_ty = &field.raw_ty;
                     quote! { <#raw_ty as persia_speedy::Readable< 'a_, C_ >>::minimum_bytes_needed() }
@@ -1631,7 +2924,7 @@ where
     } else {
         quote! {{
             let mut out = 0;
-            #(out = std::cmp::min( out, #iter );)*
+            #(out = ::core::cmp::min( out, #iter );)*
             out
         }}
     }
@@ -1639,32 +2932,133 @@ where
 
 fn impl_readable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
     let name = &input.ident;
+    let type_name = name.unraw().to_string();
     let mut types = Vec::new();
-    let (reader_body, minimum_bytes_needed_body) = match &input.data {
+    let mut predicates = Vec::new();
+    let versioned;
+    let mut bits_impl = quote! {};
+    let (reader_body, minimum_bytes_needed_body, validate, bound) = match &input.data {
         syn::Data::Struct(syn::DataStruct { ref fields, .. }) => {
             let attrs = parse_attributes::<StructAttribute>(&input.attrs)?;
             let structure = Struct::new(fields, attrs)?;
-            let (body, initializer, minimum_bytes) = readable_body(&mut types, &structure);
+            versioned = structure.is_versioned();
+            let (body, initializer, minimum_bytes) =
+                readable_body(&mut types, &mut predicates, &structure, &type_name, None);
             let reader_body = quote! {
                 #body
                 Ok( #name #initializer )
             };
-            (reader_body, minimum_bytes)
+            let (reader_body, minimum_bytes) = if structure.extensible {
+                let reader_body = quote! {
+                    persia_speedy::private::read_extensible( _reader_, |_reader_| {
+                        use persia_speedy::Reader as _;
+                        #reader_body
+                    })
+                };
+
+                (reader_body, quote! { (#minimum_bytes) + 4 })
+            } else {
+                (reader_body, minimum_bytes)
+            };
+            (
+                reader_body,
+                minimum_bytes,
+                structure.validate,
+                structure.bound,
+            )
         }
         syn::Data::Enum(syn::DataEnum { variants, .. }) => {
             let enumeration = Enum::new(&name, &input.attrs, &variants)?;
+            versioned = enumeration.is_versioned();
+            if enumeration.bits && input.generics.params.is_empty() {
+                let tags = enumeration.variants.iter().map(|variant| variant.tag);
+                let idents = enumeration.variants.iter().map(|variant| variant.ident);
+                let fallback = match enumeration.variants.iter().find(|variant| variant.other) {
+                    Some(variant) => {
+                        let ident = variant.ident;
+                        quote! { Ok( #name::#ident ) }
+                    }
+                    None => quote! {
+                        Err( persia_speedy::private::error_invalid_enum_variant_tag( #type_name, _bits_ ) )
+                    },
+                };
+
+                bits_impl = quote! {
+                    impl persia_speedy::ReadableBits for #name {
+                        #[inline]
+                        fn from_bits( _bits_: u64, _count_: u32 ) -> ::core::result::Result< Self, persia_speedy::Error > {
+                            match _bits_ {
+                                #(#tags => Ok( #name::#idents ),)*
+                                _ => #fallback
+                            }
+                        }
+                    }
+                };
+            }
+
             let mut variant_matches = Vec::with_capacity(variants.len());
             let mut variant_minimum_sizes = Vec::with_capacity(variants.len());
+            let mut other_match = None;
             for variant in enumeration.variants {
                 let tag = variant.tag_expr;
                 let unqualified_ident = &variant.ident;
                 let variant_path = quote! { #name::#unqualified_ident };
-                let (body, initializer, minimum_bytes) =
-                    readable_body(&mut types, &variant.structure);
+                if variant.other {
+                    let skip_body = if enumeration.length_delimited {
+                        quote! { _reader_.skip_bytes( _length_ )?; }
+                    } else {
+                        quote! {}
+                    };
+
+                    other_match = Some(match variant.structure.fields.len() {
+                        0 => quote! {
+                            #skip_body
+                            Ok( #variant_path )
+                        },
+                        1 => quote! {
+                            #skip_body
+                            Ok( #variant_path( kind_ as u64 ) )
+                        },
+                        _ => quote! {
+                            let _body_ = _reader_.read_vec( _length_ )?;
+                            Ok( #variant_path( kind_ as u64, _body_ ) )
+                        },
+                    });
+
+                    if variant.structure.kind != StructKind::Unit {
+                        variant_minimum_sizes.push(quote! { 0 });
+                        continue;
+                    }
+                }
+
+                let (body, initializer, minimum_bytes) = readable_body(
+                    &mut types,
+                    &mut predicates,
+                    &variant.structure,
+                    &type_name,
+                    Some(unqualified_ident),
+                );
+                let variant_body = quote! {
+                    #body
+                    Ok( #variant_path #initializer )
+                };
+
+                // A newer version of the enum could have extended this variant,
+                // so anything we don't know about has to be skipped.
+                let variant_body = if enumeration.length_delimited {
+                    quote! {
+                        persia_speedy::private::read_bounded( _reader_, _length_, |_reader_| {
+                            use persia_speedy::Reader as _;
+                            #variant_body
+                        })
+                    }
+                } else {
+                    variant_body
+                };
+
                 variant_matches.push(quote! {
                     #tag => {
-                        #body
-                        Ok( #variant_path #initializer )
+                        #variant_body
                     }
                 });
 
@@ -1698,21 +3092,42 @@ fn impl_readable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
                 (true, BasicType::VarInt64) => quote! { peek_u64_varint },
             };
 
+            let (read_length, tag_size) = if enumeration.length_delimited {
+                (
+                    quote! { let _length_ = persia_speedy::private::read_length_u32( _reader_ )?; },
+                    tag_size + 4,
+                )
+            } else {
+                (quote! {}, tag_size)
+            };
+
+            let other_match = other_match.unwrap_or_else(|| {
+                quote! {
+                    Err( persia_speedy::private::error_invalid_enum_variant_tag( #type_name, kind_ as u64 ) )
+                }
+            });
+
             let reader_body = quote! {
                 let kind_ = _reader_.#tag_reader()?;
+                #read_length
                 match kind_ {
                     #(#variant_matches),*
-                    _ => Err( persia_speedy::private::error_invalid_enum_variant() )
+                    _ => { #other_match }
                 }
             };
             let minimum_bytes_needed_body = min(variant_minimum_sizes.into_iter());
             let minimum_bytes_needed_body = if !enumeration.peek_tag {
                 quote! { (#minimum_bytes_needed_body) + #tag_size }
             } else {
-                quote! { std::cmp::max( #minimum_bytes_needed_body, #tag_size ) }
+                quote! { ::core::cmp::max( #minimum_bytes_needed_body, #tag_size ) }
             };
 
-            (reader_body, minimum_bytes_needed_body)
+            (
+                reader_body,
+                minimum_bytes_needed_body,
+                enumeration.validate,
+                enumeration.bound,
+            )
         }
         syn::Data::Union(syn::DataUnion { union_token, .. }) => {
             let message = "Unions are not supported!";
@@ -1720,11 +3135,33 @@ fn impl_readable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
         }
     };
 
-    let (impl_params, ty_params, where_clause) = common_tokens(&input, &types, Trait::Readable);
+    let reader_body = quote! {
+        persia_speedy::private::read_nested( _reader_, |_reader_| {
+            #reader_body
+        })
+    };
+
+    let reader_body = match validate {
+        Some(validate) => quote! {
+            let _value_ = #reader_body?;
+            persia_speedy::private::validate( None, #validate( &_value_ ) )?;
+            Ok( _value_ )
+        },
+        None => reader_body,
+    };
+
+    let (impl_params, ty_params, where_clause) = common_tokens(
+        &input,
+        &types,
+        &predicates,
+        &bound,
+        versioned,
+        Trait::Readable,
+    );
     let output = quote! {
         impl< 'a_, #impl_params C_: persia_speedy::Context > persia_speedy::Readable< 'a_, C_ > for #name #ty_params #where_clause {
             #[inline]
-            fn read_from< R_: persia_speedy::Reader< 'a_, C_ > >( _reader_: &mut R_ ) -> std::result::Result< Self, C_::Error > {
+            fn read_from< R_: persia_speedy::Reader< 'a_, C_ > >( _reader_: &mut R_ ) -> ::core::result::Result< Self, C_::Error > {
                 #reader_body
             }
 
@@ -1733,6 +3170,8 @@ fn impl_readable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
                 #minimum_bytes_needed_body
             }
         }
+
+        #bits_impl
     };
 
     Ok(output)
@@ -1756,22 +3195,68 @@ fn assign_to_variables<'a>(fields: impl IntoIterator<Item = &'a Field<'a>>) -> T
     }
 }
 
+/// Prefixes the given writer body with the number of bytes it writes.
+///
+/// The body is written into a collector first, so that it only has
+/// to run once even though its length is written before it.
+fn length_prefixed_body(body: TokenStream) -> TokenStream {
+    quote! {
+        let mut _collector_ = persia_speedy::private::LengthPrefixedCollector::< C_ >::new( _writer_.context_mut_if_available() );
+        {
+            use persia_speedy::Writer as _;
+            let _writer_ = &mut _collector_;
+            #body
+        }
+        persia_speedy::private::write_length_prefixed( _collector_.into_parts(), _writer_ )?;
+    }
+}
+
 fn impl_writable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
     let name = &input.ident;
+    let type_name = name.unraw().to_string();
     let mut types = Vec::new();
-    let writer_body = match input.data {
+    let mut predicates = Vec::new();
+    let versioned;
+    let mut bits_impl = quote! {};
+    let (writer_body, validate, validate_on_write, bound) = match input.data {
         syn::Data::Struct(syn::DataStruct { ref fields, .. }) => {
             let attrs = parse_attributes::<StructAttribute>(&input.attrs)?;
             let st = Struct::new(fields, attrs)?;
+            versioned = st.is_versioned();
             let assignments = assign_to_variables(&st.fields);
-            let (body, _) = writable_body(&mut types, &st);
-            quote! {
+            let (body, _) = writable_body(&mut types, &mut predicates, &st, st.validate_on_write);
+            let body = if st.extensible {
+                length_prefixed_body(body)
+            } else {
+                body
+            };
+            let body = quote! {
                 #assignments
                 #body
-            }
+            };
+
+            (body, st.validate, st.validate_on_write, st.bound)
         }
         syn::Data::Enum(syn::DataEnum { ref variants, .. }) => {
             let enumeration = Enum::new(&name, &input.attrs, &variants)?;
+            versioned = enumeration.is_versioned();
+            if enumeration.bits && input.generics.params.is_empty() {
+                let tags = enumeration.variants.iter().map(|variant| variant.tag);
+                let idents = enumeration.variants.iter().map(|variant| variant.ident);
+                bits_impl = quote! {
+                    impl persia_speedy::WritableBits for #name {
+                        #[inline]
+                        fn to_bits( &self, _count_: u32 ) -> ::core::result::Result< u64, persia_speedy::Error > {
+                            let _tag_: u64 = match *self {
+                                #(#name::#idents => #tags),*
+                            };
+
+                            persia_speedy::WritableBits::to_bits( &_tag_, _count_ )
+                        }
+                    }
+                };
+            }
+
             let tag_writer = match enumeration.tag_type {
                 BasicType::U64 => quote! { write_u64 },
                 BasicType::U32 => quote! { write_u32 },
@@ -1780,6 +3265,13 @@ fn impl_writable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
                 BasicType::U7 => quote! { write_u8 },
                 BasicType::VarInt64 => quote! { write_u64_varint },
             };
+            let (tag_ty, max_tag) = match enumeration.tag_type {
+                BasicType::U64 | BasicType::VarInt64 => (quote! { u64 }, std::u64::MAX),
+                BasicType::U32 => (quote! { u32 }, std::u32::MAX as u64),
+                BasicType::U16 => (quote! { u16 }, std::u16::MAX as u64),
+                BasicType::U8 => (quote! { u8 }, std::u8::MAX as u64),
+                BasicType::U7 => (quote! { u8 }, 0b01111111_u64),
+            };
 
             let variants: Result<Vec<_>, syn::Error> = enumeration
                 .variants
@@ -1788,13 +3280,81 @@ fn impl_writable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
                     let unqualified_ident = &variant.ident;
                     let tag_expr = &variant.tag_expr;
                     let variant_path = quote! { #name::#unqualified_ident };
-                    let (body, initializer) = writable_body(&mut types, &variant.structure);
+                    if variant.other && variant.structure.kind != StructKind::Unit {
+                        let check_tag = if max_tag != std::u64::MAX {
+                            quote! {
+                                if *t0 > #max_tag {
+                                    return Err( persia_speedy::private::error_invalid_enum_variant_tag( #type_name, *t0 ) );
+                                }
+                            }
+                        } else {
+                            quote! {}
+                        };
+
+                        // A tag of a known variant would be read back as that variant.
+                        let known_tags: Vec<_> = enumeration
+                            .variants
+                            .iter()
+                            .filter(|variant| !variant.other)
+                            .map(|variant| variant.tag)
+                            .collect();
+                        let check_tag = if known_tags.is_empty() {
+                            check_tag
+                        } else {
+                            quote! {
+                                #check_tag
+                                if let #(#known_tags)|* = *t0 {
+                                    return Err( persia_speedy::private::error_invalid_enum_variant_tag( #type_name, *t0 ) );
+                                }
+                            }
+                        };
+
+                        let snippet = if variant.structure.fields.len() == 1 {
+                            let write_length = if enumeration.length_delimited {
+                                quote! { persia_speedy::private::write_length_u32( 0, _writer_ )?; }
+                            } else {
+                                quote! {}
+                            };
+
+                            quote! {
+                                #variant_path( ref t0 ) => {
+                                    #check_tag
+                                    _writer_.#tag_writer( *t0 as #tag_ty )?;
+                                    #write_length
+                                }
+                            }
+                        } else {
+                            quote! {
+                                #variant_path( ref t0, ref t1 ) => {
+                                    #check_tag
+                                    _writer_.#tag_writer( *t0 as #tag_ty )?;
+                                    persia_speedy::private::write_length_u32( t1.len(), _writer_ )?;
+                                    _writer_.write_bytes( t1 )?;
+                                }
+                            }
+                        };
+
+                        return Ok(snippet);
+                    }
+
+                    let (body, initializer) = writable_body(
+                        &mut types,
+                        &mut predicates,
+                        &variant.structure,
+                        enumeration.validate_on_write,
+                    );
                     let write_tag = if !enumeration.peek_tag {
                         quote! { _writer_.#tag_writer( #tag_expr )?; }
                     } else {
                         quote! {}
                     };
 
+                    let body = if enumeration.length_delimited {
+                        length_prefixed_body(body)
+                    } else {
+                        body
+                    };
+
                     let snippet = quote! {
                         #variant_path #initializer => {
                             #write_tag
@@ -1806,7 +3366,13 @@ fn impl_writable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
                 })
                 .collect();
             let variants = variants?;
-            quote! { match *self { #(#variants),* } }
+            let body = quote! { match *self { #(#variants),* } };
+            (
+                body,
+                enumeration.validate,
+                enumeration.validate_on_write,
+                enumeration.bound,
+            )
         }
         syn::Data::Union(syn::DataUnion { union_token, .. }) => {
             let message = "Unions are not supported!";
@@ -1814,15 +3380,48 @@ fn impl_writable(input: syn::DeriveInput) -> Result<TokenStream, syn::Error> {
         }
     };
 
-    let (impl_params, ty_params, where_clause) = common_tokens(&input, &types, Trait::Writable);
+    let writer_body = match validate {
+        Some(ref validate) if validate_on_write => quote! {
+            persia_speedy::private::validate( None, #validate( self ) )?;
+            #writer_body
+        },
+        _ => writer_body,
+    };
+
+    let (impl_params, ty_params, where_clause) = common_tokens(
+        &input,
+        &types,
+        &predicates,
+        &bound,
+        versioned,
+        Trait::Writable,
+    );
+    // The size of a versioned type depends on the version, which only the context knows.
+    let bytes_needed_with_ctx = if versioned {
+        quote! {
+            #[inline]
+            fn bytes_needed_with_ctx( &self, context: &mut C_ ) -> ::core::result::Result< usize, C_::Error > {
+                let mut _writer_ = persia_speedy::private::SizeCalculatorCollector::new( Some( context ) );
+                persia_speedy::Writable::< C_ >::write_to( self, &mut _writer_ )?;
+                Ok( _writer_.size() )
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let output = quote! {
         impl< #impl_params C_: persia_speedy::Context > persia_speedy::Writable< C_ > for #name #ty_params #where_clause {
             #[inline]
-            fn write_to< T_: ?Sized + persia_speedy::Writer< C_ > >( &self, _writer_: &mut T_ ) -> std::result::Result< (), C_::Error > {
+            fn write_to< T_: ?Sized + persia_speedy::Writer< C_ > >( &self, _writer_: &mut T_ ) -> ::core::result::Result< (), C_::Error > {
                 #writer_body
                 Ok(())
             }
+
+            #bytes_needed_with_ctx
         }
+
+        #bits_impl
     };
 
     Ok(output)
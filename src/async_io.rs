@@ -0,0 +1,343 @@
+use std::future::Future;
+use std::io;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{self, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::circular_buffer::CircularBuffer;
+use crate::context::Context;
+use crate::framing::DEFAULT_MAX_FRAME_SIZE;
+use crate::limits::reset_limits;
+use crate::readable::{PartialReader, Readable, DEFAULT_STREAM_BUFFER_CAPACITY};
+use crate::Error;
+
+use crate::error::{error_at_offset, error_end_of_input, error_frame_is_too_big};
+
+/// An asynchronous source of bytes.
+///
+/// This is automatically implemented for every type which implements tokio's `AsyncRead`.
+pub trait AsyncReader: Unpin {
+    fn poll_read_bytes(
+        &mut self,
+        cx: &mut task::Context,
+        output: &mut [u8],
+    ) -> Poll<io::Result<usize>>;
+}
+
+/// An asynchronous sink for bytes.
+///
+/// This is automatically implemented for every type which implements tokio's `AsyncWrite`.
+pub trait AsyncWriter: Unpin {
+    fn poll_write_bytes(&mut self, cx: &mut task::Context, slice: &[u8])
+        -> Poll<io::Result<usize>>;
+    fn poll_flush_bytes(&mut self, cx: &mut task::Context) -> Poll<io::Result<()>>;
+}
+
+impl<T: AsyncRead + Unpin + ?Sized> AsyncReader for T {
+    #[inline]
+    fn poll_read_bytes(
+        &mut self,
+        cx: &mut task::Context,
+        output: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buffer = ReadBuf::new(output);
+        match Pin::new(self).poll_read(cx, &mut buffer) {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(buffer.filled().len())),
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T: AsyncWrite + Unpin + ?Sized> AsyncWriter for T {
+    #[inline]
+    fn poll_write_bytes(
+        &mut self,
+        cx: &mut task::Context,
+        slice: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(self).poll_write(cx, slice)
+    }
+
+    #[inline]
+    fn poll_flush_bytes(&mut self, cx: &mut task::Context) -> Poll<io::Result<()>> {
+        Pin::new(self).poll_flush(cx)
+    }
+}
+
+/// Asynchronously deserializes multiple consecutive values from a single stream.
+///
+/// The data is read from the stream in chunks into an internal buffer which
+/// is kept around between values, so nothing is lost when the stream
+/// contains more than one serialized value.
+///
+/// A single value can take up at most [`DEFAULT_MAX_FRAME_SIZE`] bytes by default;
+/// this can be changed with [`with_max_buffer_size`](Self::with_max_buffer_size).
+pub struct AsyncStreamDeserializer<C: Context, S: AsyncReader> {
+    context: C,
+    stream: S,
+    buffer: CircularBuffer,
+    max_buffer_size: usize,
+    bytes_wanted: usize,
+    is_at_eof: bool,
+}
+
+impl<C: Context, S: AsyncReader> AsyncStreamDeserializer<C, S> {
+    /// Creates a new deserializer with a default internal buffer size.
+    #[inline]
+    pub fn new(context: C, stream: S) -> Self {
+        Self::with_capacity(context, stream, DEFAULT_STREAM_BUFFER_CAPACITY)
+    }
+
+    /// Creates a new deserializer which will read from the stream in chunks of at least `capacity` bytes.
+    #[inline]
+    pub fn with_capacity(context: C, stream: S, capacity: usize) -> Self {
+        AsyncStreamDeserializer {
+            context,
+            stream,
+            buffer: CircularBuffer::with_capacity(std::cmp::max(capacity, 1)),
+            max_buffer_size: DEFAULT_MAX_FRAME_SIZE,
+            bytes_wanted: 0,
+            is_at_eof: false,
+        }
+    }
+
+    /// Sets the maximum number of bytes which will be buffered while reading a single value.
+    ///
+    /// If a value needs more than that an error is returned instead of reading any further.
+    #[inline]
+    pub fn with_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.max_buffer_size = std::cmp::max(max_buffer_size, 1);
+        self
+    }
+
+    /// Reads the next value from the stream.
+    ///
+    /// Resolves to `None` if the stream ended cleanly right before the next value.
+    /// If the stream ends in the middle of a value an end-of-input error is returned.
+    #[inline]
+    pub fn read_next<'a, T: Readable<'a, C>>(&mut self) -> ReadNext<'_, 'a, C, S, T> {
+        ReadNext {
+            deserializer: self,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn poll_read_next<'a, T: Readable<'a, C>>(
+        &mut self,
+        cx: &mut task::Context,
+    ) -> Poll<Option<Result<T, C::Error>>> {
+        loop {
+            let has_enough_data = self.buffer.len() > 0 && self.buffer.len() >= self.bytes_wanted;
+            if self.is_at_eof || has_enough_data {
+                if self.buffer.len() == 0 {
+                    return Poll::Ready(None);
+                }
+
+                let (result, bytes_read, bytes_missing) = {
+                    reset_limits(&mut self.context);
+                    let (a, b) = self.buffer.as_slices();
+                    let mut reader = PartialReader::new(&mut self.context, a, b.unwrap_or(&[]));
+                    let result = T::read_from(&mut reader)
+                        .map_err(|error| error_at_offset(error, reader.position()));
+                    (result, reader.position(), reader.bytes_missing())
+                };
+
+                // Even if the value was deserialized successfully it could have
+                // been cut short, e.g. due to a field with `default_on_eof`.
+                if bytes_missing == 0 || self.is_at_eof {
+                    self.bytes_wanted = 0;
+                    if result.is_ok() {
+                        self.buffer.consume(bytes_read);
+                    }
+
+                    return Poll::Ready(Some(result));
+                }
+
+                // There's no point in trying again before we get at least this much data.
+                let bytes_wanted = self.buffer.len().saturating_add(bytes_missing);
+                if bytes_wanted > self.max_buffer_size {
+                    self.bytes_wanted = 0;
+                    let error = error_frame_is_too_big(bytes_wanted, self.max_buffer_size);
+                    return Poll::Ready(Some(Err(error)));
+                }
+
+                self.bytes_wanted = bytes_wanted;
+            }
+
+            let chunk_size = std::cmp::max(
+                self.bytes_wanted.saturating_sub(self.buffer.len()),
+                self.buffer.capacity() - self.buffer.len(),
+            );
+            let chunk_size = std::cmp::min(
+                chunk_size,
+                self.max_buffer_size.saturating_sub(self.buffer.len()),
+            );
+
+            let stream = &mut self.stream;
+            let mut is_pending = false;
+            let result = self.buffer.try_append_with(chunk_size, |chunk| {
+                match stream.poll_read_bytes(cx, chunk) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => {
+                        is_pending = true;
+                        Ok(0)
+                    }
+                }
+            });
+
+            match result {
+                Ok(_) if is_pending => return Poll::Pending,
+                Ok(0) => self.is_at_eof = true,
+                Ok(_) => {}
+                Err(error) => {
+                    let error = Error::from_io_error(error);
+                    return Poll::Ready(Some(Err(<C::Error as From<Error>>::from(error))));
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    #[inline]
+    pub fn context_mut(&mut self) -> &mut C {
+        &mut self.context
+    }
+
+    /// Returns the underlying stream along with any data which was
+    /// already read from it but wasn't yet consumed.
+    pub fn into_inner(self) -> (S, Vec<u8>) {
+        (self.stream, self.buffer.to_vec())
+    }
+}
+
+/// A future returned by [`AsyncStreamDeserializer::read_next`].
+pub struct ReadNext<'d, 'a, C: Context, S: AsyncReader, T> {
+    deserializer: &'d mut AsyncStreamDeserializer<C, S>,
+    phantom: PhantomData<fn(&'a [u8]) -> T>,
+}
+
+impl<'d, 'a, C: Context, S: AsyncReader, T> Unpin for ReadNext<'d, 'a, C, S, T> {}
+
+impl<'d, 'a, C, S, T> Future for ReadNext<'d, 'a, C, S, T>
+where
+    C: Context,
+    S: AsyncReader,
+    T: Readable<'a, C>,
+{
+    type Output = Option<Result<T, C::Error>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        self.get_mut().deserializer.poll_read_next(cx)
+    }
+}
+
+/// A future returned by [`Readable::read_from_async_stream`].
+pub struct ReadFromAsyncStream<'a, C: Context, S: AsyncReader, T> {
+    deserializer: AsyncStreamDeserializer<C, S>,
+    phantom: PhantomData<fn(&'a [u8]) -> T>,
+}
+
+impl<'a, C: Context, S: AsyncReader, T> ReadFromAsyncStream<'a, C, S, T> {
+    #[inline]
+    pub(crate) fn new(context: C, stream: S) -> Self {
+        ReadFromAsyncStream {
+            deserializer: AsyncStreamDeserializer::new(context, stream),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, C: Context, S: AsyncReader, T> Unpin for ReadFromAsyncStream<'a, C, S, T> {}
+
+impl<'a, C, S, T> Future for ReadFromAsyncStream<'a, C, S, T>
+where
+    C: Context,
+    S: AsyncReader,
+    T: Readable<'a, C>,
+{
+    type Output = Result<T, C::Error>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        match self.get_mut().deserializer.poll_read_next(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result),
+            Poll::Ready(None) => Poll::Ready(Err(error_end_of_input())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A future returned by [`Writable::write_to_async_stream`](crate::Writable::write_to_async_stream).
+pub struct WriteToAsyncStream<C: Context, S: AsyncWriter> {
+    stream: S,
+    buffer: Vec<u8>,
+    position: usize,
+    error: Option<C::Error>,
+}
+
+impl<C: Context, S: AsyncWriter> WriteToAsyncStream<C, S> {
+    #[inline]
+    pub(crate) fn new(stream: S, serialized: Result<Vec<u8>, C::Error>) -> Self {
+        let (buffer, error) = match serialized {
+            Ok(buffer) => (buffer, None),
+            Err(error) => (Vec::new(), Some(error)),
+        };
+
+        WriteToAsyncStream {
+            stream,
+            buffer,
+            position: 0,
+            error,
+        }
+    }
+}
+
+impl<C: Context, S: AsyncWriter> Unpin for WriteToAsyncStream<C, S> {}
+
+impl<C: Context, S: AsyncWriter> Future for WriteToAsyncStream<C, S> {
+    type Output = Result<(), C::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Err(error));
+        }
+
+        while this.position < this.buffer.len() {
+            match this
+                .stream
+                .poll_write_bytes(cx, &this.buffer[this.position..])
+            {
+                Poll::Ready(Ok(0)) => {
+                    let error =
+                        io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer");
+                    let error = Error::from_io_error(error);
+                    return Poll::Ready(Err(<C::Error as From<Error>>::from(error)));
+                }
+                Poll::Ready(Ok(bytes_written)) => this.position += bytes_written,
+                Poll::Ready(Err(error)) => {
+                    let error = Error::from_io_error(error);
+                    return Poll::Ready(Err(<C::Error as From<Error>>::from(error)));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match this.stream.poll_flush_bytes(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(())),
+            Poll::Ready(Err(error)) => {
+                let error = Error::from_io_error(error);
+                Poll::Ready(Err(<C::Error as From<Error>>::from(error)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use core::convert::TryFrom;
+
+use crate::context::Context;
+use crate::endianness::Endianness;
+use crate::reader::Reader;
+use crate::writer::Writer;
+use crate::Error;
+
+use crate::error::{error_too_many_bits, error_value_does_not_fit_in_bits};
+
+// Bits are packed starting from the least significant bit of each byte
+// for little endian contexts, and from the most significant bit for big
+// endian ones; in both cases the bytes themselves are consumed in order.
+
+#[inline(always)]
+fn mask(count: u32) -> u64 {
+    if count >= 64 {
+        !0
+    } else {
+        (1 << count) - 1
+    }
+}
+
+/// A reader which reads individual bits from another reader.
+///
+/// Bytes are read from the underlying reader as needed; any bits which
+/// were left unread in the last byte are discarded when this is dropped.
+pub struct BitReader<'r, R> {
+    reader: &'r mut R,
+    buffer: u8,
+    available: u32,
+}
+
+impl<'r, R> BitReader<'r, R> {
+    #[inline]
+    pub fn new(reader: &'r mut R) -> Self {
+        BitReader {
+            reader,
+            buffer: 0,
+            available: 0,
+        }
+    }
+
+    /// Reads up to 64 bits and returns them in the lowest bits of the result.
+    pub fn read_bits<'a, C>(&mut self, count: u32) -> Result<u64, C::Error>
+    where
+        C: Context,
+        R: Reader<'a, C>,
+    {
+        if count > 64 {
+            return Err(error_too_many_bits(count));
+        }
+
+        let endianness = self.reader.endianness();
+        let mut value = 0;
+        let mut remaining = count;
+        while remaining > 0 {
+            if self.available == 0 {
+                self.buffer = self.reader.read_u8()?;
+                self.available = 8;
+            }
+
+            let chunk = core::cmp::min(self.available, remaining);
+            let buffer = u64::from(self.buffer);
+            match endianness {
+                Endianness::LittleEndian => {
+                    let consumed = 8 - self.available;
+                    value |= ((buffer >> consumed) & mask(chunk)) << (count - remaining);
+                }
+                Endianness::BigEndian => {
+                    let bits = (buffer >> (self.available - chunk)) & mask(chunk);
+                    value = (value << chunk) | bits;
+                }
+            }
+
+            self.available -= chunk;
+            remaining -= chunk;
+        }
+
+        Ok(value)
+    }
+}
+
+/// A writer which writes individual bits into another writer.
+///
+/// Full bytes are written into the underlying writer as soon as they're complete;
+/// `flush` has to be called at the end to pad the last byte with zeros and write it.
+pub struct BitWriter<'w, W: ?Sized> {
+    writer: &'w mut W,
+    buffer: u8,
+    used: u32,
+}
+
+impl<'w, W: ?Sized> BitWriter<'w, W> {
+    #[inline]
+    pub fn new(writer: &'w mut W) -> Self {
+        BitWriter {
+            writer,
+            buffer: 0,
+            used: 0,
+        }
+    }
+
+    /// Writes the lowest `count` bits of `value`; up to 64 bits can be written at a time.
+    pub fn write_bits<C>(&mut self, count: u32, value: u64) -> Result<(), C::Error>
+    where
+        C: Context,
+        W: Writer<C>,
+    {
+        if count > 64 {
+            return Err(error_too_many_bits(count));
+        }
+
+        let endianness = self.writer.endianness();
+        let mut remaining = count;
+        while remaining > 0 {
+            let chunk = core::cmp::min(8 - self.used, remaining);
+            match endianness {
+                Endianness::LittleEndian => {
+                    let bits = (value >> (count - remaining)) & mask(chunk);
+                    self.buffer |= (bits << self.used) as u8;
+                }
+                Endianness::BigEndian => {
+                    let bits = (value >> (remaining - chunk)) & mask(chunk);
+                    self.buffer |= (bits << (8 - self.used - chunk)) as u8;
+                }
+            }
+
+            self.used += chunk;
+            remaining -= chunk;
+            if self.used == 8 {
+                self.writer.write_u8(self.buffer)?;
+                self.buffer = 0;
+                self.used = 0;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes out the last partially filled byte, if there is one.
+    pub fn flush<C>(&mut self) -> Result<(), C::Error>
+    where
+        C: Context,
+        W: Writer<C>,
+    {
+        if self.used > 0 {
+            self.writer.write_u8(self.buffer)?;
+            self.buffer = 0;
+            self.used = 0;
+        }
+
+        Ok(())
+    }
+}
+
+/// Types which can be read from a given number of bits with `#[speedy(bits = ...)]`.
+pub trait ReadableBits: Sized {
+    /// Converts the lowest `count` bits of `bits` into a value.
+    fn from_bits(bits: u64, count: u32) -> Result<Self, Error>;
+}
+
+/// Types which can be written as a given number of bits with `#[speedy(bits = ...)]`.
+pub trait WritableBits {
+    /// Converts the value into bits, failing if it doesn't fit into `count` bits.
+    fn to_bits(&self, count: u32) -> Result<u64, Error>;
+}
+
+macro_rules! impl_unsigned_bits {
+    ($($type:ty)*) => { $(
+        impl ReadableBits for $type {
+            #[inline]
+            fn from_bits(bits: u64, count: u32) -> Result<Self, Error> {
+                <$type>::try_from(bits).map_err(|_| error_value_does_not_fit_in_bits(count))
+            }
+        }
+
+        impl WritableBits for $type {
+            #[inline]
+            fn to_bits(&self, count: u32) -> Result<u64, Error> {
+                let value = *self as u64;
+                if value & !mask(count) != 0 {
+                    return Err(error_value_does_not_fit_in_bits(count));
+                }
+
+                Ok(value)
+            }
+        }
+    )* };
+}
+
+macro_rules! impl_signed_bits {
+    ($($type:ty)*) => { $(
+        impl ReadableBits for $type {
+            #[inline]
+            fn from_bits(bits: u64, count: u32) -> Result<Self, Error> {
+                let value = match count {
+                    0 => 0,
+                    64 => bits as i64,
+                    _ => ((bits << (64 - count)) as i64) >> (64 - count),
+                };
+
+                <$type>::try_from(value).map_err(|_| error_value_does_not_fit_in_bits(count))
+            }
+        }
+
+        impl WritableBits for $type {
+            #[inline]
+            fn to_bits(&self, count: u32) -> Result<u64, Error> {
+                let value = *self as i64;
+                let fits = match count {
+                    0 => value == 0,
+                    64 => true,
+                    _ => {
+                        let limit = 1_i64 << (count - 1);
+                        value >= -limit && value < limit
+                    }
+                };
+
+                if !fits {
+                    return Err(error_value_does_not_fit_in_bits(count));
+                }
+
+                Ok(value as u64 & mask(count))
+            }
+        }
+    )* };
+}
+
+impl_unsigned_bits!(u8 u16 u32 u64 usize);
+impl_signed_bits!(i8 i16 i32 i64 isize);
+
+impl ReadableBits for bool {
+    #[inline]
+    fn from_bits(bits: u64, _count: u32) -> Result<Self, Error> {
+        Ok(bits != 0)
+    }
+}
+
+impl WritableBits for bool {
+    #[inline]
+    fn to_bits(&self, count: u32) -> Result<u64, Error> {
+        (*self as u8).to_bits(count)
+    }
+}
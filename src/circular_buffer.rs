@@ -1,4 +1,6 @@
-use std::ops::Range;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Range;
 
 pub struct CircularBuffer {
     buffer: Box<[u8]>,
@@ -159,7 +161,7 @@ impl CircularBuffer {
         unsafe {
             buffer.set_len(capacity);
             if cfg!(debug_assertions) {
-                std::ptr::write_bytes(buffer.as_mut_ptr(), 0xFF, buffer.len());
+                core::ptr::write_bytes(buffer.as_mut_ptr(), 0xFF, buffer.len());
             }
         }
 
@@ -180,6 +182,7 @@ impl CircularBuffer {
         self.length
     }
 
+    #[cfg(feature = "std")]
     pub fn capacity(&self) -> usize {
         self.buffer.len()
     }
@@ -226,6 +229,7 @@ impl CircularBuffer {
         self.position = 0;
     }
 
+    #[cfg(feature = "std")]
     pub fn try_append_with<E>(
         &mut self,
         size: usize,
@@ -247,6 +251,7 @@ impl CircularBuffer {
         (&self.buffer[a], b.map(|b| &self.buffer[b]))
     }
 
+    #[cfg(feature = "std")]
     #[inline(always)]
     pub fn as_slices_of_length(&self, length: usize) -> (&[u8], Option<&[u8]>) {
         assert!(length <= self.length);
@@ -258,7 +263,6 @@ impl CircularBuffer {
         }
     }
 
-    #[cfg(test)]
     pub fn to_vec(&self) -> Vec<u8> {
         let mut output = Vec::with_capacity(self.len());
         let (a, b) = self.as_slices();
@@ -271,7 +275,6 @@ impl CircularBuffer {
         output
     }
 
-    #[cfg(test)]
     pub fn extend_from_slice(&mut self, slice: &[u8]) {
         self.reserve(slice.len());
         let (range_1, range_2) = empty(self.position, self.length, self.buffer.len(), slice.len());
@@ -295,9 +298,10 @@ impl CircularBuffer {
         }
     }
 
+    #[cfg(feature = "std")]
     #[inline(always)]
     pub fn consume_into(&mut self, buffer: &mut [u8]) {
-        let length = std::cmp::min(self.length, buffer.len());
+        let length = core::cmp::min(self.length, buffer.len());
         if self.position + length < self.buffer.len() {
             buffer[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
             self.position += length;
@@ -312,6 +316,7 @@ impl CircularBuffer {
         self.consume_into_slow(buffer)
     }
 
+    #[cfg(feature = "std")]
     #[inline(never)]
     fn consume_into_slow(&mut self, buffer: &mut [u8]) {
         if buffer.is_empty() {
@@ -0,0 +1,103 @@
+//! Misuses of the derive attributes which are supposed to be rejected at compile time.
+
+/// A `bound` can only be given once on a struct:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// #[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+/// #[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+/// struct Struct<T> {
+///     value: T,
+/// }
+/// ```
+///
+/// ...and on a field:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// struct Struct<T> {
+///     #[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+///     #[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+///     value: T,
+/// }
+/// ```
+///
+/// ...and each of its `read` and `write` parts only once:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// #[speedy(bound(read = "T: Readable<'a_, C_>", read = "T: Readable<'a_, C_>"))]
+/// struct Struct<T> {
+///     value: T,
+/// }
+/// ```
+pub struct DuplicateBound;
+
+/// A `bound` cannot be put on an enum variant:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// enum Enum<T> {
+///     #[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+///     Variant(T),
+/// }
+/// ```
+pub struct BoundOnVariant;
+
+/// The predicates of a `bound` have to be valid:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// #[speedy(bound = "T: +")]
+/// struct Struct<T> {
+///     value: T,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// struct Struct<T> {
+///     #[speedy(bound(read = "T Readable<'a_, C_>", write = "T: Writable<C_>"))]
+///     value: T,
+/// }
+/// ```
+pub struct InvalidBoundPredicate;
+
+/// A `default` does nothing on its own, so it has to be combined with an attribute which uses it:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// struct Struct {
+///     #[speedy(default = 7)]
+///     value: u16,
+/// }
+/// ```
+pub struct DefaultWithoutFallback;
+
+/// Only enums with unit variants can be packed into bits:
+///
+/// ```compile_fail
+/// use persia_speedy::{Readable, Writable};
+///
+/// #[derive(Readable, Writable)]
+/// #[speedy(bits)]
+/// enum Enum {
+///     A,
+///     B(u8),
+/// }
+/// ```
+pub struct BitsOnEnumWithFields;
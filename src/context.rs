@@ -1,8 +1,15 @@
 use crate::endianness::Endianness;
+use crate::limits::Limits;
 
 pub trait Context {
     type Error: From<crate::Error> + crate::IsEof;
     fn endianness(&self) -> Endianness;
+
+    /// Returns the decoding limits which should be enforced, if any.
+    #[inline(always)]
+    fn limits_mut(&mut self) -> Option<&mut Limits> {
+        None
+    }
 }
 
 impl Context for Endianness {
@@ -14,10 +21,10 @@ impl Context for Endianness {
     }
 }
 
-#[derive(Default)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct LittleEndian {}
 
-#[derive(Default)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct BigEndian {}
 
 impl Context for LittleEndian {
@@ -38,6 +45,53 @@ impl Context for BigEndian {
     }
 }
 
+/// A context which knows which version of the serialization format is being used.
+///
+/// Needed by types with fields marked with `#[speedy(since = ...)]` or `#[speedy(until = ...)]`.
+pub trait Versioned: Context {
+    fn version(&self) -> u32;
+}
+
+/// A context which wraps another context and attaches a format version to it.
+#[derive(Copy, Clone, Debug)]
+pub struct WithVersion<C> {
+    context: C,
+    version: u32,
+}
+
+impl<C: Context> WithVersion<C> {
+    #[inline]
+    pub fn new(context: C, version: u32) -> Self {
+        WithVersion { context, version }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> C {
+        self.context
+    }
+}
+
+impl<C: Context> Context for WithVersion<C> {
+    type Error = C::Error;
+
+    #[inline(always)]
+    fn endianness(&self) -> Endianness {
+        self.context.endianness()
+    }
+
+    #[inline(always)]
+    fn limits_mut(&mut self) -> Option<&mut Limits> {
+        self.context.limits_mut()
+    }
+}
+
+impl<C: Context> Versioned for WithVersion<C> {
+    #[inline(always)]
+    fn version(&self) -> u32 {
+        self.version
+    }
+}
+
 pub trait DefaultContext {
     type Context;
 }
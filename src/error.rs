@@ -1,11 +1,46 @@
-use std::{fmt, io};
+use core::fmt;
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::limits::Limit;
 
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
+    context: Option<Box<ErrorContext>>,
+}
+
+/// Extra information about where exactly an error happened.
+///
+/// This is boxed and only allocated when an error occurs so that
+/// it doesn't make the happy path any slower.
+#[derive(Debug, Default)]
+struct ErrorContext {
+    offset: Option<usize>,
+    type_name: Option<&'static str>,
+    tag: Option<u64>,
+    root: Option<&'static str>,
+    // Stored innermost first, since it's built while the error bubbles up.
+    path: Vec<PathSegment>,
+}
+
+#[derive(Debug)]
+enum PathSegment {
+    Field(&'static str),
+    Index(usize),
 }
 
+/// The kind of an [`Error`].
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ErrorKind {
     InvalidChar,
     InvalidEnumVariant,
@@ -14,6 +49,7 @@ pub enum ErrorKind {
     ZeroNonZero,
     OutOfRangeLength,
     OutOfRangeUsize,
+    OutOfRangeVarInt,
     UnexpectedEndOfInput,
     UnexpectedEndOfOutputBuffer,
     InputBufferIsTooSmall {
@@ -28,39 +64,149 @@ pub enum ErrorKind {
     LengthIsNotTheSameAsLengthAttribute {
         field_name: &'static str,
     },
+    LengthIsAboveMaxLengthAttribute {
+        field_name: &'static str,
+        length: usize,
+        max_length: usize,
+    },
+    ValueIsNotInRangeAttribute {
+        field_name: &'static str,
+    },
+    ValidationFailed {
+        /// The field which failed to validate, or `None` if the whole value did.
+        field_name: Option<&'static str>,
+        error: Box<dyn core::error::Error + Send + Sync>,
+    },
     ExpectedConstant {
         constant: &'static [u8],
     },
+    FrameIsTooBig {
+        frame_size: usize,
+        max_frame_size: usize,
+    },
+    FrameHasTrailingBytes {
+        frame_size: usize,
+        trailing_bytes: usize,
+    },
+    LimitExceeded {
+        limit: Limit,
+        maximum: usize,
+    },
+    RecursionLimitExceeded,
+    ValueDoesNotFitInBits {
+        bits: u32,
+    },
+    TooManyBits {
+        bits: u32,
+    },
+    AlreadyMutablyBorrowed,
+    InputIsNotBorrowable,
+    UnalignedBorrow,
+    BorrowNeedsEndiannessConversion,
 
+    #[cfg(feature = "std")]
     IoError(io::Error),
+    Custom(Box<dyn core::error::Error + Send + Sync>),
 }
 
 impl Error {
     #[inline]
     fn new(kind: ErrorKind) -> Self {
-        Error { kind }
+        Error {
+            kind,
+            context: None,
+        }
+    }
+
+    /// Returns the byte offset in the input at which the decoding failed, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.context.as_ref().and_then(|context| context.offset)
+    }
+
+    /// Returns the name of the innermost derived type which failed to decode, if known.
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.context.as_ref().and_then(|context| context.type_name)
     }
 
+    /// Returns the offending tag value, if this error was caused by an invalid enum tag.
+    pub fn tag(&self) -> Option<u64> {
+        self.context.as_ref().and_then(|context| context.tag)
+    }
+
+    /// Returns the path to the field which failed to decode, e.g. `Outer.items[3].name`.
+    pub fn field_path(&self) -> Option<String> {
+        let context = self.context.as_ref()?;
+        if context.root.is_none() && context.path.is_empty() {
+            return None;
+        }
+
+        let mut path = String::new();
+        if let Some(root) = context.root {
+            path.push_str(root);
+        }
+
+        for segment in context.path.iter().rev() {
+            match *segment {
+                PathSegment::Field(name) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(name);
+                }
+                PathSegment::Index(index) => {
+                    path.push_str(&format!("[{}]", index));
+                }
+            }
+        }
+
+        Some(path)
+    }
+
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        self.context.get_or_insert_with(Default::default)
+    }
+
+    /// Creates a custom error with the given message.
     pub fn custom(message: impl fmt::Display) -> Self {
-        // The LLVM optimizer doesn't like us adding a new variant,
-        // so instead we reuse the `IoError` one.
-        Error {
-            kind: ErrorKind::IoError(io::Error::new(io::ErrorKind::Other, message.to_string())),
+        Error::custom_error(message.to_string())
+    }
+
+    /// Creates a custom error which wraps the given error.
+    ///
+    /// The wrapped error can be retrieved later with [`downcast_ref`](Error::downcast_ref).
+    pub fn custom_error(error: impl Into<Box<dyn core::error::Error + Send + Sync>>) -> Self {
+        Error::new(ErrorKind::Custom(error.into()))
+    }
+
+    #[inline]
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Returns a reference to the wrapped custom error if it's of type `E`.
+    pub fn downcast_ref<E: core::error::Error + 'static>(&self) -> Option<&E> {
+        match self.kind {
+            ErrorKind::Custom(ref error) | ErrorKind::ValidationFailed { ref error, .. } => {
+                error.downcast_ref::<E>()
+            }
+            _ => None,
         }
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     pub(crate) fn from_io_error(error: io::Error) -> Self {
-        Error {
-            kind: ErrorKind::IoError(error),
-        }
+        Error::new(ErrorKind::IoError(error))
     }
 }
 
+#[cfg(feature = "std")]
 impl From<Error> for io::Error {
     fn from(error: Error) -> Self {
-        if let ErrorKind::IoError(error) = error.kind {
-            return error;
+        match error.kind {
+            ErrorKind::IoError(error) => return error,
+            ErrorKind::Custom(error) => return io::Error::new(io::ErrorKind::Other, error),
+            _ => {}
         }
 
         let is_eof = error.is_eof();
@@ -74,6 +220,14 @@ impl From<Error> for io::Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Error::from_io_error(error)
+    }
+}
+
 #[inline]
 pub fn get_error_kind(error: &Error) -> &ErrorKind {
     &error.kind
@@ -81,6 +235,35 @@ pub fn get_error_kind(error: &Error) -> &ErrorKind {
 
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_kind(fmt)?;
+        if self.context.is_none() {
+            return Ok(());
+        }
+
+        let mut details = Vec::new();
+        if let Some(type_name) = self.type_name() {
+            details.push(format!("type: {}", type_name));
+        }
+        if let Some(tag) = self.tag() {
+            details.push(format!("tag: {}", tag));
+        }
+        if let Some(path) = self.field_path() {
+            details.push(format!("field: {}", path));
+        }
+        if let Some(offset) = self.offset() {
+            details.push(format!("offset: {}", offset));
+        }
+
+        if !details.is_empty() {
+            write!(fmt, " ({})", details.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error {
+    fn fmt_kind(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self.kind {
             ErrorKind::InvalidChar => write!(fmt, "out of range char"),
             ErrorKind::InvalidEnumVariant => write!(fmt, "invalid enum variant"),
@@ -90,9 +273,20 @@ impl fmt::Display for Error {
                 write!(fmt, "a field which is supposed to be non-zero is zero")
             }
             ErrorKind::OutOfRangeLength => write!(fmt, "out of range length"),
+            ErrorKind::InputIsNotBorrowable => {
+                write!(fmt, "the input cannot be borrowed from by this reader")
+            }
+            ErrorKind::UnalignedBorrow => {
+                write!(fmt, "the input is not aligned well enough to be borrowed")
+            }
+            ErrorKind::BorrowNeedsEndiannessConversion => write!(
+                fmt,
+                "the input cannot be borrowed since it needs an endianness conversion"
+            ),
             ErrorKind::OutOfRangeUsize => {
                 write!(fmt, "value cannot fit into an usize on this architecture")
             }
+            ErrorKind::OutOfRangeVarInt => write!(fmt, "varint is out of range for its type"),
             ErrorKind::UnexpectedEndOfInput => write!(fmt, "unexpected end of input"),
             ErrorKind::UnexpectedEndOfOutputBuffer => {
                 write!(fmt, "unexpected end of output buffer")
@@ -118,20 +312,83 @@ impl fmt::Display for Error {
                 "the length of '{}' is not the same as its 'length' attribute",
                 field_name
             ),
+            ErrorKind::LengthIsAboveMaxLengthAttribute {
+                field_name,
+                length,
+                max_length,
+            } => write!(
+                fmt,
+                "the length of '{}' is {}, which is above its 'max_length' attribute of {}",
+                field_name, length, max_length
+            ),
+            ErrorKind::ValueIsNotInRangeAttribute { field_name } => write!(
+                fmt,
+                "the value of '{}' is not within its 'range' attribute",
+                field_name
+            ),
+            ErrorKind::ValidationFailed {
+                field_name: Some(field_name),
+                ref error,
+            } => write!(fmt, "validation of '{}' failed: {}", field_name, error),
+            ErrorKind::ValidationFailed {
+                field_name: None,
+                ref error,
+            } => write!(fmt, "validation failed: {}", error),
             ErrorKind::ExpectedConstant { constant } => write!(
                 fmt,
                 "expected a predefined {} bytes(s) long constant",
                 constant.len()
             ),
+            ErrorKind::FrameIsTooBig {
+                frame_size,
+                max_frame_size,
+            } => write!(
+                fmt,
+                "frame is too big; expected at most {} bytes, got {}",
+                max_frame_size, frame_size
+            ),
+            ErrorKind::FrameHasTrailingBytes {
+                frame_size,
+                trailing_bytes,
+            } => write!(
+                fmt,
+                "frame of {} bytes has {} trailing bytes which were not read",
+                frame_size, trailing_bytes
+            ),
+            ErrorKind::LimitExceeded { limit, maximum } => {
+                let name = match limit {
+                    Limit::TotalBytes => "total number of bytes read",
+                    Limit::CollectionLength => "collection length",
+                    Limit::Allocation => "number of bytes allocated",
+                };
+
+                write!(fmt, "exceeded the maximum {} of {}", name, maximum)
+            }
+            ErrorKind::RecursionLimitExceeded => write!(fmt, "recursion limit exceeded"),
+            ErrorKind::ValueDoesNotFitInBits { bits } => {
+                write!(fmt, "value does not fit in {} bits", bits)
+            }
+            ErrorKind::TooManyBits { bits } => write!(
+                fmt,
+                "at most 64 bits can be read or written at a time; tried {}",
+                bits
+            ),
+            ErrorKind::AlreadyMutablyBorrowed => write!(fmt, "value is already mutably borrowed"),
+            #[cfg(feature = "std")]
             ErrorKind::IoError(ref error) => write!(fmt, "{}", error),
+            ErrorKind::Custom(ref error) => write!(fmt, "{}", error),
         }
     }
 }
 
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self.kind {
+            #[cfg(feature = "std")]
             ErrorKind::IoError(ref error) => Some(error),
+            ErrorKind::Custom(ref error) | ErrorKind::ValidationFailed { ref error, .. } => {
+                Some(&**error)
+            }
             _ => None,
         }
     }
@@ -139,20 +396,77 @@ impl std::error::Error for Error {
 
 pub trait IsEof {
     fn is_eof(&self) -> bool;
+
+    #[doc(hidden)]
+    #[inline]
+    fn speedy_add_field(&mut self, _type_name: &'static str, _field_name: &'static str) {}
+
+    #[doc(hidden)]
+    #[inline]
+    fn speedy_add_index(&mut self, _index: usize) {}
+
+    #[doc(hidden)]
+    #[inline]
+    fn speedy_set_offset(&mut self, _offset: usize) {}
 }
 
 impl IsEof for Error {
     fn is_eof(&self) -> bool {
         match self.kind {
             ErrorKind::UnexpectedEndOfInput | ErrorKind::UnexpectedEndOfOutputBuffer => true,
-            ErrorKind::IoError(ref error) => error.kind() == std::io::ErrorKind::UnexpectedEof,
+            #[cfg(feature = "std")]
+            ErrorKind::IoError(ref error) => error.kind() == io::ErrorKind::UnexpectedEof,
             _ => false,
         }
     }
+
+    fn speedy_add_field(&mut self, type_name: &'static str, field_name: &'static str) {
+        let context = self.context_mut();
+        context.type_name.get_or_insert(type_name);
+        context.root = Some(type_name);
+        context.path.push(PathSegment::Field(field_name));
+    }
+
+    fn speedy_add_index(&mut self, index: usize) {
+        let context = self.context_mut();
+        context.root = None;
+        context.path.push(PathSegment::Index(index));
+    }
+
+    fn speedy_set_offset(&mut self, offset: usize) {
+        self.context_mut().offset.get_or_insert(offset);
+    }
+}
+
+#[cold]
+pub fn error_add_field<E>(mut error: E, type_name: &'static str, field_name: &'static str) -> E
+where
+    E: IsEof,
+{
+    error.speedy_add_field(type_name, field_name);
+    error
+}
+
+#[cold]
+pub fn error_add_index<E>(mut error: E, index: usize) -> E
+where
+    E: IsEof,
+{
+    error.speedy_add_index(index);
+    error
 }
 
 #[cold]
-pub fn error_invalid_string_utf8<T>(_: std::string::FromUtf8Error) -> T
+pub fn error_at_offset<E>(mut error: E, offset: usize) -> E
+where
+    E: IsEof,
+{
+    error.speedy_set_offset(offset);
+    error
+}
+
+#[cold]
+pub fn error_invalid_string_utf8<T>(_: alloc::string::FromUtf8Error) -> T
 where
     T: From<Error>,
 {
@@ -160,7 +474,7 @@ where
 }
 
 #[cold]
-pub fn error_invalid_str_utf8<T>(_: std::str::Utf8Error) -> T
+pub fn error_invalid_str_utf8<T>(_: core::str::Utf8Error) -> T
 where
     T: From<Error>,
 {
@@ -177,6 +491,44 @@ where
     }))
 }
 
+#[cold]
+pub fn error_length_is_above_max_length_attribute<T>(
+    field_name: &'static str,
+    length: usize,
+    max_length: usize,
+) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::LengthIsAboveMaxLengthAttribute {
+        field_name,
+        length,
+        max_length,
+    }))
+}
+
+#[cold]
+pub fn error_value_is_not_in_range_attribute<T>(field_name: &'static str) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::ValueIsNotInRangeAttribute {
+        field_name,
+    }))
+}
+
+#[cold]
+pub fn error_validation_failed<T, E>(field_name: Option<&'static str>, error: E) -> T
+where
+    T: From<Error>,
+    E: Into<Box<dyn core::error::Error + Send + Sync>>,
+{
+    T::from(Error::new(ErrorKind::ValidationFailed {
+        field_name,
+        error: error.into(),
+    }))
+}
+
 #[cold]
 pub fn error_out_of_range_length<T>() -> T
 where
@@ -193,6 +545,19 @@ where
     T::from(Error::new(ErrorKind::InvalidEnumVariant))
 }
 
+#[cold]
+pub fn error_invalid_enum_variant_tag<T>(type_name: &'static str, tag: u64) -> T
+where
+    T: From<Error>,
+{
+    let mut error = Error::new(ErrorKind::InvalidEnumVariant);
+    let context = error.context_mut();
+    context.type_name = Some(type_name);
+    context.root = Some(type_name);
+    context.tag = Some(tag);
+    T::from(error)
+}
+
 #[cold]
 pub fn error_out_of_range_char<T>() -> T
 where
@@ -209,6 +574,14 @@ where
     T::from(Error::new(ErrorKind::OutOfRangeUsize))
 }
 
+#[cold]
+pub fn error_out_of_range_varint<T>() -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::OutOfRangeVarInt))
+}
+
 #[cold]
 pub fn error_end_of_input<T>() -> T
 where
@@ -247,6 +620,103 @@ where
     }))
 }
 
+#[cfg(feature = "std")]
+#[cold]
+pub fn error_frame_is_too_big<T>(frame_size: usize, max_frame_size: usize) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::FrameIsTooBig {
+        frame_size,
+        max_frame_size,
+    }))
+}
+
+#[cfg(feature = "std")]
+#[cold]
+pub fn error_frame_has_trailing_bytes<T>(frame_size: usize, trailing_bytes: usize) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::FrameHasTrailingBytes {
+        frame_size,
+        trailing_bytes,
+    }))
+}
+
+#[cold]
+pub fn error_limit_exceeded<T>(limit: Limit, maximum: usize) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::LimitExceeded { limit, maximum }))
+}
+
+#[cold]
+pub fn error_recursion_limit_exceeded<T>() -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::RecursionLimitExceeded))
+}
+
+#[cold]
+pub fn error_value_does_not_fit_in_bits<T>(bits: u32) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::ValueDoesNotFitInBits { bits }))
+}
+
+#[cold]
+pub fn error_too_many_bits<T>(bits: u32) -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::TooManyBits { bits }))
+}
+
+#[cold]
+pub fn error_already_mutably_borrowed<T>() -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::AlreadyMutablyBorrowed))
+}
+
+#[cold]
+pub fn error_custom<T, E>(error: E) -> T
+where
+    T: From<Error>,
+    E: Into<Box<dyn core::error::Error + Send + Sync>>,
+{
+    T::from(Error::custom_error(error))
+}
+
+#[cold]
+pub fn error_input_is_not_borrowable<T>() -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::InputIsNotBorrowable))
+}
+
+#[cold]
+pub fn error_unaligned_borrow<T>() -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::UnalignedBorrow))
+}
+
+#[cold]
+pub fn error_borrow_needs_endianness_conversion<T>() -> T
+where
+    T: From<Error>,
+{
+    T::from(Error::new(ErrorKind::BorrowNeedsEndiannessConversion))
+}
+
 #[cold]
 pub fn error_zero_non_zero<T>() -> T
 where
@@ -255,6 +725,7 @@ where
     T::from(Error::new(ErrorKind::ZeroNonZero))
 }
 
+#[cfg(feature = "std")]
 #[cold]
 pub fn error_invalid_system_time<T>() -> T
 where
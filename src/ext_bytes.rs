@@ -1,5 +1,5 @@
 use crate::private::write_length;
-use std::mem;
+use core::mem;
 use {
     crate::{Context, Readable, Reader, Writable, Writer},
     bytes::Bytes,
@@ -1,6 +1,6 @@
 use crate::private::write_length;
-use std::hash::{BuildHasher, Hash};
-use std::mem;
+use core::hash::{BuildHasher, Hash};
+use core::mem;
 use {
     crate::{Context, Readable, Reader, Writable, Writer},
     hashbrown::HashMap,
@@ -1,5 +1,5 @@
+use core::convert::TryInto;
 use ndarray::{Dimension, OwnedRepr};
-use std::convert::TryInto;
 use {
     crate::{Context, Readable, Reader, Writable, Writer},
     ndarray::ArrayBase,
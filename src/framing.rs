@@ -0,0 +1,548 @@
+use std::io::{Read, Write};
+
+use crate::context::Context;
+use crate::limits::reset_limits;
+use crate::private;
+use crate::readable::{Readable, StreamReader, DEFAULT_STREAM_BUFFER_CAPACITY};
+use crate::reader::Reader;
+use crate::writable::Writable;
+use crate::writer::Writer;
+use crate::Error;
+
+use crate::error::{
+    error_end_of_output_buffer, error_frame_has_trailing_bytes, error_frame_is_too_big,
+};
+
+#[cfg(feature = "tokio-util")]
+use {
+    crate::readable::BufferReader,
+    crate::IsEof,
+    bytes::BytesMut,
+    std::io,
+    std::marker::PhantomData,
+    tokio_util::codec::{Decoder, Encoder},
+};
+
+/// The default maximum size of a single frame, in bytes.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 8 * 1024 * 1024;
+
+const MAX_HEADER_SIZE: usize = 9;
+
+/// The type of the length header which precedes every frame.
+///
+/// These correspond to the values accepted by the `length_type` attribute.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LengthType {
+    U7,
+    U8,
+    U16,
+    U32,
+    U64,
+    U64Varint,
+}
+
+impl Default for LengthType {
+    #[inline]
+    fn default() -> Self {
+        LengthType::U32
+    }
+}
+
+impl LengthType {
+    #[inline]
+    fn write_length<C, W>(self, length: usize, writer: &mut W) -> Result<(), C::Error>
+    where
+        C: Context,
+        W: ?Sized + Writer<C>,
+    {
+        match self {
+            LengthType::U7 => private::write_length_u7(length, writer),
+            LengthType::U8 => private::write_length_u8(length, writer),
+            LengthType::U16 => private::write_length_u16(length, writer),
+            LengthType::U32 => private::write_length_u32(length, writer),
+            LengthType::U64 => private::write_length_u64(length, writer),
+            LengthType::U64Varint => private::write_length_u64_varint(length, writer),
+        }
+    }
+
+    #[inline]
+    fn read_length<'a, C, R>(self, reader: &mut R) -> Result<usize, C::Error>
+    where
+        C: Context,
+        R: Reader<'a, C>,
+    {
+        match self {
+            LengthType::U7 => private::read_length_u7(reader),
+            LengthType::U8 => private::read_length_u8(reader),
+            LengthType::U16 => private::read_length_u16(reader),
+            LengthType::U32 => private::read_length_u32(reader),
+            LengthType::U64 => private::read_length_u64(reader),
+            LengthType::U64Varint => private::read_length_u64_varint(reader),
+        }
+    }
+
+    #[inline]
+    fn max_header_size(self) -> usize {
+        match self {
+            LengthType::U7 | LengthType::U8 => 1,
+            LengthType::U16 => 2,
+            LengthType::U32 => 4,
+            LengthType::U64 => 8,
+            LengthType::U64Varint => MAX_HEADER_SIZE,
+        }
+    }
+}
+
+trait FrameBuffer {
+    fn as_mut_slice(&mut self) -> &mut [u8];
+    fn len(&self) -> usize;
+    fn truncate(&mut self, length: usize);
+    fn extend_from_slice(&mut self, slice: &[u8]);
+}
+
+impl FrameBuffer for Vec<u8> {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        Vec::as_mut_slice(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    #[inline]
+    fn truncate(&mut self, length: usize) {
+        Vec::truncate(self, length)
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, slice: &[u8]) {
+        Vec::extend_from_slice(self, slice)
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl FrameBuffer for BytesMut {
+    #[inline]
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self[..]
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        BytesMut::len(self)
+    }
+
+    #[inline]
+    fn truncate(&mut self, length: usize) {
+        BytesMut::truncate(self, length)
+    }
+
+    #[inline]
+    fn extend_from_slice(&mut self, slice: &[u8]) {
+        BytesMut::extend_from_slice(self, slice)
+    }
+}
+
+struct FrameWriter<'r, C, B> {
+    context: &'r mut C,
+    buffer: &'r mut B,
+}
+
+impl<'r, C: Context, B: FrameBuffer> Writer<C> for FrameWriter<'r, C, B> {
+    #[inline]
+    fn write_bytes(&mut self, slice: &[u8]) -> Result<(), C::Error> {
+        self.buffer.extend_from_slice(slice);
+        Ok(())
+    }
+
+    #[inline]
+    fn context(&self) -> &C {
+        self.context
+    }
+
+    #[inline]
+    fn context_mut(&mut self) -> &mut C {
+        self.context
+    }
+}
+
+struct HeaderWriter<'r, C> {
+    context: &'r mut C,
+    header: [u8; MAX_HEADER_SIZE],
+    length: usize,
+}
+
+impl<'r, C: Context> Writer<C> for HeaderWriter<'r, C> {
+    #[inline]
+    fn write_bytes(&mut self, slice: &[u8]) -> Result<(), C::Error> {
+        let header = self
+            .header
+            .get_mut(self.length..self.length + slice.len())
+            .ok_or_else(error_end_of_output_buffer)?;
+        header.copy_from_slice(slice);
+        self.length += slice.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn context(&self) -> &C {
+        self.context
+    }
+
+    #[inline]
+    fn context_mut(&mut self) -> &mut C {
+        self.context
+    }
+}
+
+fn write_frame<C, T, B>(
+    context: &mut C,
+    length_type: LengthType,
+    max_frame_size: usize,
+    value: &T,
+    buffer: &mut B,
+) -> Result<(), C::Error>
+where
+    C: Context,
+    T: ?Sized + Writable<C>,
+    B: FrameBuffer,
+{
+    let position = buffer.len();
+    let result = write_frame_at(
+        context,
+        length_type,
+        max_frame_size,
+        value,
+        buffer,
+        position,
+    );
+    if result.is_err() {
+        buffer.truncate(position);
+    }
+
+    result
+}
+
+/// Writes the value first, after room for the biggest possible header, and only then
+/// its length, so that the length doesn't have to be calculated beforehand.
+fn write_frame_at<C, T, B>(
+    context: &mut C,
+    length_type: LengthType,
+    max_frame_size: usize,
+    value: &T,
+    buffer: &mut B,
+    position: usize,
+) -> Result<(), C::Error>
+where
+    C: Context,
+    T: ?Sized + Writable<C>,
+    B: FrameBuffer,
+{
+    let max_header_size = length_type.max_header_size();
+    buffer.extend_from_slice(&[0; MAX_HEADER_SIZE][..max_header_size]);
+    value.write_to(&mut FrameWriter {
+        context: &mut *context,
+        buffer: &mut *buffer,
+    })?;
+
+    let body_position = position + max_header_size;
+    let length = buffer.len() - body_position;
+    if length > max_frame_size {
+        return Err(error_frame_is_too_big(length, max_frame_size));
+    }
+
+    let mut header = HeaderWriter {
+        context,
+        header: [0; MAX_HEADER_SIZE],
+        length: 0,
+    };
+    length_type.write_length(length, &mut header)?;
+
+    let header_position = body_position - header.length;
+    let slice = buffer.as_mut_slice();
+    slice[header_position..body_position].copy_from_slice(&header.header[..header.length]);
+    if header_position > position {
+        slice.copy_within(header_position.., position);
+        buffer.truncate(buffer.len() - (header_position - position));
+    }
+
+    Ok(())
+}
+
+/// Reads a value from a frame, which it has to take up entirely.
+fn read_whole_frame<'a, C, T>(context: C, frame: &'a [u8]) -> Result<T, C::Error>
+where
+    C: Context,
+    T: Readable<'a, C>,
+{
+    let (value, length) = T::read_with_length_from_buffer_with_ctx(context, frame);
+    let value = value?;
+    if length != frame.len() {
+        return Err(error_frame_has_trailing_bytes(
+            frame.len(),
+            frame.len() - length,
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Writes values into a blocking stream, each one prefixed with its length.
+pub struct FramedWriter<C: Context, S: Write> {
+    context: C,
+    stream: S,
+    length_type: LengthType,
+    max_frame_size: usize,
+    buffer: Vec<u8>,
+}
+
+impl<C: Context, S: Write> FramedWriter<C, S> {
+    #[inline]
+    pub fn new(context: C, stream: S) -> Self {
+        FramedWriter {
+            context,
+            stream,
+            length_type: LengthType::default(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            buffer: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_length_type(mut self, length_type: LengthType) -> Self {
+        self.length_type = length_type;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Serializes a given value and writes it as a single frame.
+    pub fn write_frame<T: ?Sized + Writable<C>>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.buffer.clear();
+        write_frame(
+            &mut self.context,
+            self.length_type,
+            self.max_frame_size,
+            value,
+            &mut self.buffer,
+        )?;
+
+        self.stream.write_all(&self.buffer).map_err(|error| {
+            let error = Error::from_io_error(error);
+            <C::Error as From<Error>>::from(error)
+        })
+    }
+
+    pub fn flush(&mut self) -> Result<(), C::Error> {
+        self.stream.flush().map_err(|error| {
+            let error = Error::from_io_error(error);
+            <C::Error as From<Error>>::from(error)
+        })
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+/// Reads length prefixed values from a blocking stream.
+pub struct FramedReader<C: Context, S: Read> {
+    reader: StreamReader<C, S>,
+    length_type: LengthType,
+    max_frame_size: usize,
+    frame: Vec<u8>,
+}
+
+impl<C: Context + Clone, S: Read> FramedReader<C, S> {
+    #[inline]
+    pub fn new(context: C, stream: S) -> Self {
+        FramedReader {
+            reader: StreamReader::new(context, stream, DEFAULT_STREAM_BUFFER_CAPACITY),
+            length_type: LengthType::default(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            frame: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_length_type(mut self, length_type: LengthType) -> Self {
+        self.length_type = length_type;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Reads the next frame and deserializes a value from it.
+    ///
+    /// Returns `None` if the stream ended cleanly right before the next frame.
+    /// The returned value can borrow from the reader's internal frame buffer.
+    pub fn read_frame<'r, T: Readable<'r, C>>(&'r mut self) -> Option<Result<T, C::Error>> {
+        match self.reader.is_at_end() {
+            Ok(true) => None,
+            Ok(false) => Some(self.read_frame_impl()),
+            Err(error) => Some(Err(error)),
+        }
+    }
+
+    fn read_frame_impl<'r, T: Readable<'r, C>>(&'r mut self) -> Result<T, C::Error> {
+        reset_limits(self.reader.context_mut());
+        let length = self.length_type.read_length(&mut self.reader)?;
+        if length > self.max_frame_size {
+            return Err(error_frame_is_too_big(length, self.max_frame_size));
+        }
+
+        self.frame.resize(length, 0);
+        self.reader.read_bytes(&mut self.frame)?;
+
+        let mut context = self.reader.context().clone();
+        reset_limits(&mut context);
+        read_whole_frame(context, &self.frame)
+    }
+
+    /// Returns the underlying stream along with any data which was
+    /// already read from it but wasn't yet consumed.
+    #[inline]
+    pub fn into_inner(self) -> (S, Vec<u8>) {
+        self.reader.into_inner()
+    }
+}
+
+/// A length delimited codec for use with `tokio_util::codec`.
+#[cfg(feature = "tokio-util")]
+pub struct SpeedyCodec<T, C: Context> {
+    context: C,
+    length_type: LengthType,
+    max_frame_size: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "tokio-util")]
+impl<T, C: Context> SpeedyCodec<T, C> {
+    #[inline]
+    pub fn new(context: C) -> Self {
+        SpeedyCodec {
+            context,
+            length_type: LengthType::default(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn with_length_type(mut self, length_type: LengthType) -> Self {
+        self.length_type = length_type;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<T, C: Context + Default> Default for SpeedyCodec<T, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<T, C> Encoder<T> for SpeedyCodec<T, C>
+where
+    T: Writable<C>,
+    C: Context,
+    C::Error: From<io::Error>,
+{
+    type Error = C::Error;
+
+    #[inline]
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        write_frame(
+            &mut self.context,
+            self.length_type,
+            self.max_frame_size,
+            &item,
+            dst,
+        )
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<'r, T, C> Encoder<&'r T> for SpeedyCodec<T, C>
+where
+    T: Writable<C>,
+    C: Context,
+    C::Error: From<io::Error>,
+{
+    type Error = C::Error;
+
+    #[inline]
+    fn encode(&mut self, item: &'r T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        write_frame(
+            &mut self.context,
+            self.length_type,
+            self.max_frame_size,
+            item,
+            dst,
+        )
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<T, C> Decoder for SpeedyCodec<T, C>
+where
+    T: for<'a> Readable<'a, C>,
+    C: Context + Clone,
+    C::Error: From<io::Error>,
+{
+    type Item = T;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let (length, header_length) = {
+            let mut reader = BufferReader::new(self.context.clone(), &src[..]);
+            match self.length_type.read_length(&mut reader) {
+                Ok(length) => (length, reader.position()),
+                Err(ref error) if error.is_eof() => return Ok(None),
+                Err(error) => return Err(error),
+            }
+        };
+
+        if length > self.max_frame_size {
+            return Err(error_frame_is_too_big(length, self.max_frame_size));
+        }
+
+        let frame_length = header_length + length;
+        if src.len() < frame_length {
+            src.reserve(frame_length - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(frame_length);
+        read_whole_frame(self.context.clone(), &frame[header_length..]).map(Some)
+    }
+}
@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::circular_buffer::CircularBuffer;
+use crate::context::Context;
+use crate::limits::reset_limits;
+use crate::readable::{PartialReader, Readable};
+
+use crate::error::error_at_offset;
+
+/// The outcome of [`IncrementalDecoder::decode`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Decoded<T> {
+    /// A whole value was decoded.
+    Complete(T),
+    /// At least this many more bytes are needed before a value can be decoded.
+    Needed(usize),
+}
+
+/// Decodes consecutive values from data which arrives piece by piece.
+///
+/// This is meant for non-blocking event loops where the data is pushed
+/// into the decoder as it becomes available instead of being pulled
+/// from a stream.
+pub struct IncrementalDecoder<C: Context, T> {
+    context: C,
+    buffer: CircularBuffer,
+    bytes_wanted: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<C: Context, T: Readable<'static, C>> IncrementalDecoder<C, T> {
+    #[inline]
+    pub fn new(context: C) -> Self {
+        IncrementalDecoder {
+            context,
+            buffer: CircularBuffer::with_capacity(0),
+            bytes_wanted: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Appends a chunk of data to the decoder's internal buffer.
+    #[inline]
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Tries to decode the next value from the data fed so far.
+    ///
+    /// If there isn't enough data this returns how many more bytes are necessary
+    /// at the very least; no actual decoding will be attempted until that many
+    /// bytes are fed into the decoder.
+    pub fn decode(&mut self) -> Result<Decoded<T>, C::Error> {
+        let bytes_wanted = core::cmp::max(self.bytes_wanted, T::minimum_bytes_needed());
+        if self.buffer.len() < bytes_wanted {
+            return Ok(Decoded::Needed(bytes_wanted - self.buffer.len()));
+        }
+
+        let (result, bytes_read, bytes_missing) = {
+            reset_limits(&mut self.context);
+            let (a, b) = self.buffer.as_slices();
+            let mut reader = PartialReader::new(&mut self.context, a, b.unwrap_or(&[]));
+            let result = T::read_from(&mut reader)
+                .map_err(|error| error_at_offset(error, reader.position()));
+            (result, reader.position(), reader.bytes_missing())
+        };
+
+        if bytes_missing > 0 {
+            self.bytes_wanted = self.buffer.len() + bytes_missing;
+            return Ok(Decoded::Needed(bytes_missing));
+        }
+
+        let value = result?;
+        self.bytes_wanted = 0;
+        if bytes_read > 0 {
+            self.buffer.consume(bytes_read);
+        }
+
+        Ok(Decoded::Complete(value))
+    }
+
+    /// Returns the number of bytes which were fed into the decoder but weren't consumed yet.
+    #[inline]
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    #[inline]
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    #[inline]
+    pub fn context_mut(&mut self) -> &mut C {
+        &mut self.context
+    }
+
+    /// Returns the data which was fed into the decoder but wasn't consumed yet.
+    #[inline]
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buffer.to_vec()
+    }
+}
+
+#[test]
+fn test_incremental_decoder() {
+    let mut decoder: IncrementalDecoder<_, Vec<u16>> =
+        IncrementalDecoder::new(crate::Endianness::LittleEndian);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Needed(4));
+
+    decoder.feed(&[3, 0]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Needed(2));
+
+    decoder.feed(&[0, 0]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Needed(6));
+
+    decoder.feed(&[1, 0, 2, 0, 3]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Needed(1));
+
+    decoder.feed(&[0, 0]);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Complete(vec![1, 2, 3]));
+    assert_eq!(decoder.buffered_len(), 1);
+    assert_eq!(decoder.decode().unwrap(), Decoded::Needed(3));
+    assert_eq!(decoder.into_inner(), vec![0]);
+}
+
+#[test]
+fn test_incremental_decoder_does_not_reparse_until_enough_data() {
+    use crate::{Reader, Writable};
+    use std::cell::Cell;
+
+    thread_local! {
+        static PARSE_COUNT: Cell<usize> = Cell::new(0);
+    }
+
+    struct Counted(Vec<u8>);
+
+    impl<'a, C: Context> Readable<'a, C> for Counted {
+        fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+            PARSE_COUNT.with(|count| count.set(count.get() + 1));
+            Ok(Counted(reader.read_value()?))
+        }
+    }
+
+    let data = vec![0xAA_u8; 1000]
+        .write_to_vec_with_ctx(crate::Endianness::LittleEndian)
+        .unwrap();
+
+    let mut decoder: IncrementalDecoder<_, Counted> =
+        IncrementalDecoder::new(crate::Endianness::LittleEndian);
+    let mut value = None;
+    for chunk in data.chunks(10) {
+        decoder.feed(chunk);
+        if let Decoded::Complete(decoded) = decoder.decode().unwrap() {
+            value = Some(decoded);
+        }
+    }
+
+    assert_eq!(value.unwrap().0, vec![0xAA_u8; 1000]);
+    assert_eq!(PARSE_COUNT.with(|count| count.get()), 2);
+}
+
+#[test]
+fn test_incremental_decoder_checks_limits_before_asking_for_more_data() {
+    use crate::{Limited, Limits};
+
+    let context = Limited::new(
+        crate::Endianness::LittleEndian,
+        Limits::new()
+            .with_max_collection_length(10)
+            .with_max_allocation(1024),
+    );
+
+    let mut decoder: IncrementalDecoder<_, Vec<u8>> = IncrementalDecoder::new(context);
+    decoder.feed(&[0xff, 0xff, 0xff, 0x7f, 0x00]);
+    let error = decoder.decode().unwrap_err();
+    match crate::private::get_error_kind(&error) {
+        crate::private::ErrorKind::LimitExceeded { .. } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
@@ -1,12 +1,20 @@
 #![cfg_attr(feature = "external_doc", feature(external_doc))]
 #![cfg_attr(feature = "external_doc", doc(include = "../README.md"))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 mod error;
 #[macro_use]
 mod utils;
+mod bits;
 mod circular_buffer;
 mod context;
 mod endianness;
+#[cfg(feature = "std")]
+mod framing;
+mod incremental;
+mod limits;
 mod readable;
 mod readable_impl;
 mod reader;
@@ -15,6 +23,12 @@ mod writable;
 mod writable_impl;
 mod writer;
 
+#[cfg(feature = "tokio")]
+mod async_io;
+
+#[cfg(all(doctest, feature = "persia-speedy-derive"))]
+mod compile_fail_tests;
+
 #[cfg(feature = "bytes")]
 mod ext_bytes;
 #[cfg(feature = "chrono")]
@@ -36,16 +50,33 @@ pub mod private;
 #[cfg(feature = "persia-speedy-derive")]
 pub use persia_speedy_derive::{Readable, Writable};
 
-pub use crate::readable::Readable;
-pub use crate::reader::Reader;
+pub use crate::readable::{BufferIter, BufferReader, Readable};
+#[cfg(feature = "std")]
+pub use crate::readable::{StreamDeserializer, StreamIter};
+pub use crate::reader::{BoundedReader, Reader};
 
 pub use crate::writable::Writable;
 pub use crate::writer::Writer;
 
-pub use crate::context::{BigEndian, Context, LittleEndian};
+pub use crate::bits::{BitReader, BitWriter, ReadableBits, WritableBits};
+pub use crate::context::{BigEndian, Context, LittleEndian, Versioned, WithVersion};
 pub use crate::endianness::Endianness;
 
-pub use crate::error::{Error, IsEof};
+#[cfg(feature = "tokio-util")]
+pub use crate::framing::SpeedyCodec;
+#[cfg(feature = "std")]
+pub use crate::framing::{FramedReader, FramedWriter, LengthType, DEFAULT_MAX_FRAME_SIZE};
+pub use crate::incremental::{Decoded, IncrementalDecoder};
+pub use crate::limits::{Limit, Limited, Limits, DEFAULT_MAX_DEPTH};
+pub use crate::varint::{VarInt, ZigZag};
+
+pub use crate::error::{Error, ErrorKind, IsEof};
+
+#[cfg(feature = "tokio")]
+pub use crate::async_io::{
+    AsyncReader, AsyncStreamDeserializer, AsyncWriter, ReadFromAsyncStream, ReadNext,
+    WriteToAsyncStream,
+};
 
 #[cfg(test)]
 mod tests {
@@ -158,6 +189,97 @@ mod tests {
         assert_eq!(value, 2);
     }
 
+    #[test]
+    fn buffer_reader_reads_multiple_values() {
+        let data = vec![1, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 5, 6];
+        let mut reader = super::BufferReader::new(Endianness::LittleEndian, &data);
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read_value::<u16>().unwrap(), 1);
+        assert_eq!(reader.position(), 2);
+
+        let value: Cow<[u8]> = reader.read_value().unwrap();
+        assert_eq!(&*value, &[3, 0]);
+        assert!(matches!(value, Cow::Borrowed(_)));
+        assert_eq!(reader.position(), 8);
+        assert_eq!(reader.remaining(), &[0, 0, 4, 5, 6]);
+        assert!(!reader.is_empty());
+
+        reader.skip_bytes(2).unwrap();
+        assert_eq!(
+            reader.read_value::<SimpleStruct>().unwrap(),
+            SimpleStruct { a: 4, b: 5, c: 6 }
+        );
+        assert!(reader.is_empty());
+        assert!(reader.read_u8().is_err());
+    }
+
+    #[test]
+    fn iter_from_buffer_with_default_ctx() {
+        let data = vec![1, 0, 2, 0, 3, 0];
+        let values: Vec<u16> = u16::iter_from_buffer(&data)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn iter_from_buffer_with_truncated_value() {
+        let data = vec![1, 0, 2];
+        let mut iter = u16::iter_from_buffer_with_ctx(Endianness::LittleEndian, &data);
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        assert_eq!(iter.position(), 2);
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+        assert_eq!(iter.remaining(), &[2]);
+    }
+
+    #[test]
+    fn iter_from_stream_with_default_ctx() {
+        let data = vec![1, 0, 2, 0, 3, 0];
+        let values: Vec<u16> = u16::iter_from_stream(io::Cursor::new(data))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn iter_from_stream_with_truncated_value() {
+        let data = vec![1, 0, 2];
+        let mut iter =
+            u16::iter_from_stream_with_ctx(Endianness::LittleEndian, io::Cursor::new(data));
+        assert_eq!(iter.next().unwrap().unwrap(), 1);
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(crate::IsEof::is_eof(&error));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn stream_deserializer_keeps_buffered_data_between_values() {
+        let data = vec![1, 2, 3, 4, 5, 6, 7];
+        let mut deserializer =
+            super::StreamDeserializer::with_capacity(Endianness::NATIVE, io::Cursor::new(data), 2);
+        let value: SimpleStruct = deserializer.read_next().unwrap().unwrap();
+        assert_eq!(value, SimpleStruct { a: 1, b: 2, c: 3 });
+        let value: SimpleStruct = deserializer.read_next().unwrap().unwrap();
+        assert_eq!(value, SimpleStruct { a: 4, b: 5, c: 6 });
+        assert!(!deserializer.is_at_end().unwrap());
+
+        let (mut stream, remaining) = deserializer.into_inner();
+        assert_eq!(remaining, vec![7]);
+        assert_eq!(stream.position(), 7);
+        assert_eq!(io::Read::read(&mut stream, &mut [0]).unwrap(), 0);
+    }
+
+    #[test]
+    fn stream_deserializer_returns_none_at_end_of_stream() {
+        let data = vec![1, 2, 3];
+        let mut deserializer = super::StreamDeserializer::new(Endianness::NATIVE, &data[..]);
+        let value: Option<Result<SimpleStruct, _>> = deserializer.read_next();
+        assert_eq!(value.unwrap().unwrap(), SimpleStruct { a: 1, b: 2, c: 3 });
+        let value: Option<Result<SimpleStruct, _>> = deserializer.read_next();
+        assert!(value.is_none());
+    }
+
     #[test]
     fn write_to_buffer_with_default_ctx() {
         let mut buffer = [0, 0];
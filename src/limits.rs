@@ -0,0 +1,260 @@
+use core::mem;
+
+use crate::context::{Context, Versioned};
+use crate::endianness::Endianness;
+use crate::reader::Reader;
+use crate::Error;
+
+use crate::error::{error_limit_exceeded, error_recursion_limit_exceeded};
+
+/// The default maximum nesting depth, used unless a different one is set with [`Limits::with_max_depth`].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// A limit which was exceeded while decoding.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Limit {
+    TotalBytes,
+    CollectionLength,
+    Allocation,
+}
+
+/// Limits imposed on the decoder, meant to be used when reading untrusted input.
+///
+/// Besides the limits themselves this also keeps track of how much
+/// of each limit was already used up.
+#[derive(Clone, Debug, Default)]
+pub struct Limits {
+    max_total_bytes: Option<usize>,
+    max_collection_length: Option<usize>,
+    max_allocation: Option<usize>,
+    max_depth: Option<usize>,
+    bytes_read: usize,
+    bytes_allocated: usize,
+    depth: usize,
+}
+
+impl Limits {
+    /// Creates a new set of limits with every limit disabled,
+    /// except for the depth which is limited to [`DEFAULT_MAX_DEPTH`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the total number of bytes which can be consumed from the input.
+    #[inline]
+    pub fn with_max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+
+    /// Limits the number of elements a single collection can have.
+    #[inline]
+    pub fn with_max_collection_length(mut self, max_collection_length: usize) -> Self {
+        self.max_collection_length = Some(max_collection_length);
+        self
+    }
+
+    /// Limits the cumulative number of bytes which can be allocated for collections.
+    #[inline]
+    pub fn with_max_allocation(mut self, max_allocation: usize) -> Self {
+        self.max_allocation = Some(max_allocation);
+        self
+    }
+
+    /// Limits how deeply values can be nested inside of each other.
+    ///
+    /// Every derived type and every `Vec`, `Option` and map counts as one level.
+    /// Defaults to [`DEFAULT_MAX_DEPTH`].
+    #[inline]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Returns the number of bytes which were consumed so far.
+    #[inline]
+    pub fn bytes_read(&self) -> usize {
+        self.bytes_read
+    }
+
+    /// Returns the number of bytes which were allocated for collections so far.
+    #[inline]
+    pub fn bytes_allocated(&self) -> usize {
+        self.bytes_allocated
+    }
+
+    /// Resets the usage counters while keeping the limits themselves intact.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.bytes_read = 0;
+        self.bytes_allocated = 0;
+        self.depth = 0;
+    }
+
+    fn enter_nested(&mut self) -> Result<(), Error> {
+        enter_nested(&mut self.depth, self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH))
+    }
+
+    fn leave_nested(&mut self) {
+        leave_nested(&mut self.depth);
+    }
+
+    fn add_bytes_read(&mut self, length: usize) -> Result<(), Error> {
+        self.bytes_read = self.bytes_read.saturating_add(length);
+        match self.max_total_bytes {
+            Some(max_total_bytes) if self.bytes_read > max_total_bytes => {
+                Err(error_limit_exceeded(Limit::TotalBytes, max_total_bytes))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn add_collection(&mut self, length: usize, element_size: usize) -> Result<(), Error> {
+        if let Some(max_collection_length) = self.max_collection_length {
+            if length > max_collection_length {
+                return Err(error_limit_exceeded(
+                    Limit::CollectionLength,
+                    max_collection_length,
+                ));
+            }
+        }
+
+        self.bytes_allocated = self
+            .bytes_allocated
+            .saturating_add(length.saturating_mul(element_size));
+        match self.max_allocation {
+            Some(max_allocation) if self.bytes_allocated > max_allocation => {
+                Err(error_limit_exceeded(Limit::Allocation, max_allocation))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A context which wraps another context and enforces the given [`Limits`].
+#[derive(Clone, Debug)]
+pub struct Limited<C> {
+    context: C,
+    limits: Limits,
+}
+
+impl<C: Context> Limited<C> {
+    #[inline]
+    pub fn new(context: C, limits: Limits) -> Self {
+        Limited { context, limits }
+    }
+
+    #[inline]
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> C {
+        self.context
+    }
+}
+
+impl<C: Context> Context for Limited<C> {
+    type Error = C::Error;
+
+    #[inline(always)]
+    fn endianness(&self) -> Endianness {
+        self.context.endianness()
+    }
+
+    #[inline(always)]
+    fn limits_mut(&mut self) -> Option<&mut Limits> {
+        Some(&mut self.limits)
+    }
+}
+
+impl<C: Versioned> Versioned for Limited<C> {
+    #[inline(always)]
+    fn version(&self) -> u32 {
+        self.context.version()
+    }
+}
+
+#[inline(always)]
+pub(crate) fn reset_limits<C: Context>(context: &mut C) {
+    if let Some(limits) = context.limits_mut() {
+        limits.reset();
+    }
+}
+
+#[inline(always)]
+pub(crate) fn track_bytes_read<C: Context>(context: &mut C, length: usize) -> Result<(), C::Error> {
+    match context.limits_mut() {
+        Some(limits) => limits
+            .add_bytes_read(length)
+            .map_err(<C::Error as From<Error>>::from),
+        None => Ok(()),
+    }
+}
+
+#[inline(always)]
+pub(crate) fn track_collection<C: Context, T>(
+    context: &mut C,
+    length: usize,
+) -> Result<(), C::Error> {
+    match context.limits_mut() {
+        Some(limits) => limits
+            .add_collection(length, mem::size_of::<T>())
+            .map_err(<C::Error as From<Error>>::from),
+        None => Ok(()),
+    }
+}
+
+fn enter_nested(depth: &mut usize, max_depth: usize) -> Result<(), Error> {
+    if *depth >= max_depth {
+        return Err(error_recursion_limit_exceeded());
+    }
+
+    *depth += 1;
+    Ok(())
+}
+
+fn leave_nested(depth: &mut usize) {
+    *depth = depth.saturating_sub(1);
+}
+
+/// Reads a value one nesting level deeper, failing if the depth limit would be exceeded.
+///
+/// The depth is tracked by the context's [`Limits`] if it has any, and
+/// otherwise by the reader itself with the [`DEFAULT_MAX_DEPTH`] as the limit.
+#[inline(always)]
+pub fn read_nested<'a, C, R, T, F>(reader: &mut R, callback: F) -> Result<T, C::Error>
+where
+    C: Context,
+    R: Reader<'a, C>,
+    F: FnOnce(&mut R) -> Result<T, C::Error>,
+{
+    if let Some(limits) = reader.context_mut().limits_mut() {
+        limits
+            .enter_nested()
+            .map_err(<C::Error as From<Error>>::from)?;
+
+        let result = callback(reader);
+        if let Some(limits) = reader.context_mut().limits_mut() {
+            limits.leave_nested();
+        }
+
+        return result;
+    }
+
+    match reader.nesting_depth_mut() {
+        Some(depth) => {
+            enter_nested(depth, DEFAULT_MAX_DEPTH).map_err(<C::Error as From<Error>>::from)?
+        }
+        None => return callback(reader),
+    }
+
+    let result = callback(reader);
+    if let Some(depth) = reader.nesting_depth_mut() {
+        leave_nested(depth);
+    }
+
+    result
+}
@@ -1,16 +1,136 @@
 use {
     crate::{
         error::{error_expected_constant, error_invalid_str_utf8, error_invalid_string_utf8},
-        Context, Error, Readable, Reader, Writable, Writer,
+        BitReader, BitWriter, BoundedReader, Context, Error, Readable, ReadableBits, Reader,
+        Versioned, Writable, WritableBits, Writer,
     },
-    std::borrow::Cow,
+    alloc::{string::String, vec::Vec},
 };
 
+pub use alloc::borrow::Cow;
+pub use alloc::collections::VecDeque;
+
 pub use crate::error::{
-    error_invalid_enum_variant, error_length_is_not_the_same_as_length_attribute,
-    error_out_of_range_length, get_error_kind, ErrorKind,
+    error_add_field, error_add_index, error_custom, error_invalid_enum_variant,
+    error_invalid_enum_variant_tag, error_length_is_above_max_length_attribute,
+    error_length_is_not_the_same_as_length_attribute, error_out_of_range_length,
+    error_value_is_not_in_range_attribute, get_error_kind, ErrorKind,
 };
+pub use crate::limits::read_nested;
 pub use crate::varint::VarInt64;
+pub use crate::writable::{LengthPrefixedCollector, SizeCalculatorCollector};
+
+/// Reads a single field, attaching its name to the error if it fails.
+#[inline(always)]
+pub fn read_field<'a, C, R, T, F>(
+    reader: &mut R,
+    type_name: &'static str,
+    field_name: &'static str,
+    callback: F,
+) -> Result<T, C::Error>
+where
+    C: Context,
+    R: Reader<'a, C>,
+    F: FnOnce(&mut R) -> Result<T, C::Error>,
+{
+    match callback(reader) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(error_add_field(error, type_name, field_name)),
+    }
+}
+
+/// Checks whether a value is within the range given in a `range` attribute.
+#[inline]
+pub fn is_in_range<T, R>(range: R, value: &T) -> bool
+where
+    T: PartialOrd,
+    R: core::ops::RangeBounds<T>,
+{
+    range.contains(value)
+}
+
+/// Converts the result of a user supplied `validate` function into our error type.
+#[inline]
+pub fn validate<E, V>(field_name: Option<&'static str>, result: Result<(), V>) -> Result<(), E>
+where
+    E: From<Error>,
+    V: Into<alloc::boxed::Box<dyn core::error::Error + Send + Sync>>,
+{
+    result.map_err(|error| crate::error::error_validation_failed(field_name, error))
+}
+
+/// Reads a field marked with the `bits` attribute.
+#[inline]
+pub fn read_bits_field<'a, C, R, T>(reader: &mut BitReader<R>, count: u32) -> Result<T, C::Error>
+where
+    C: Context,
+    R: Reader<'a, C>,
+    T: ReadableBits,
+{
+    let bits = reader.read_bits(count)?;
+    T::from_bits(bits, count).map_err(<C::Error as From<Error>>::from)
+}
+
+/// Writes a field marked with the `bits` attribute.
+#[inline]
+pub fn write_bits_field<C, W, T>(
+    writer: &mut BitWriter<W>,
+    count: u32,
+    value: &T,
+) -> Result<(), C::Error>
+where
+    C: Context,
+    W: ?Sized + Writer<C>,
+    T: ?Sized + WritableBits,
+{
+    let bits = value.to_bits(count)?;
+    writer.write_bits(count, bits)
+}
+
+/// Returns the version of the format the writer's context is using.
+///
+/// There is no version when the writer has no context, which is the case in `Writable::bytes_needed`.
+#[inline]
+pub fn writer_version<C, W>(writer: &mut W) -> Option<u32>
+where
+    C: Versioned,
+    W: ?Sized + Writer<C>,
+{
+    writer
+        .context_mut_if_available()
+        .map(|context| context.version())
+}
+
+/// Reads a length prefixed value, skipping whatever bytes were left unread at its end.
+#[inline]
+pub fn read_extensible<'a, C, R, T, F>(reader: &mut R, callback: F) -> Result<T, C::Error>
+where
+    C: Context,
+    R: Reader<'a, C>,
+    F: FnOnce(&mut BoundedReader<R>) -> Result<T, C::Error>,
+{
+    let length = read_length_u32(reader)?;
+    read_bounded(reader, length, callback)
+}
+
+/// Reads a value of a known length, skipping whatever bytes were left unread at its end.
+#[inline]
+pub fn read_bounded<'a, C, R, T, F>(
+    reader: &mut R,
+    length: usize,
+    callback: F,
+) -> Result<T, C::Error>
+where
+    C: Context,
+    R: Reader<'a, C>,
+    F: FnOnce(&mut BoundedReader<R>) -> Result<T, C::Error>,
+{
+    let mut reader = BoundedReader::new(reader, length);
+    let value = callback(&mut reader)?;
+    let remaining = reader.remaining();
+    reader.skip_bytes(remaining)?;
+    Ok(value)
+}
 
 #[inline]
 pub fn vec_to_string<E>(bytes: Vec<u8>) -> Result<String, E>
@@ -26,7 +146,7 @@ where
     E: From<Error>,
 {
     match bytes {
-        Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+        Cow::Borrowed(bytes) => core::str::from_utf8(bytes)
             .map(Cow::Borrowed)
             .map_err(error_invalid_str_utf8),
         Cow::Owned(bytes) => String::from_utf8(bytes)
@@ -60,20 +180,37 @@ where
     C: Context,
     W: ?Sized + Writer<C>,
 {
-    if length as u64 > std::u32::MAX as u64 {
+    if length as u64 > core::u32::MAX as u64 {
         return Err(error_out_of_range_length());
     }
 
     writer.write_u32(length as u32)
 }
 
+/// Writes the parts of a `LengthPrefixedCollector`.
+#[inline]
+pub fn write_length_prefixed<C, W>(
+    (length, bytes): (usize, Option<Vec<u8>>),
+    writer: &mut W,
+) -> Result<(), C::Error>
+where
+    C: Context,
+    W: ?Sized + Writer<C>,
+{
+    write_length_u32(length, writer)?;
+    match bytes {
+        Some(bytes) => writer.write_bytes(&bytes),
+        None => writer.write_zeros(length),
+    }
+}
+
 #[inline]
 pub fn write_length_u16<C, W>(length: usize, writer: &mut W) -> Result<(), C::Error>
 where
     C: Context,
     W: ?Sized + Writer<C>,
 {
-    if length as u64 > std::u16::MAX as u64 {
+    if length as u64 > core::u16::MAX as u64 {
         return Err(error_out_of_range_length());
     }
 
@@ -86,7 +223,7 @@ where
     C: Context,
     W: ?Sized + Writer<C>,
 {
-    if length as u64 > std::u8::MAX as u64 {
+    if length as u64 > core::u8::MAX as u64 {
         return Err(error_out_of_range_length());
     }
 
@@ -122,7 +259,7 @@ where
     R: Reader<'a, C>,
 {
     let length: u64 = VarInt64::read_from(reader)?.into();
-    if length > std::usize::MAX as u64 {
+    if length > core::usize::MAX as u64 {
         return Err(error_out_of_range_length());
     }
 
@@ -136,7 +273,7 @@ where
     R: Reader<'a, C>,
 {
     let length = reader.read_u64()?;
-    if length > std::usize::MAX as u64 {
+    if length > core::usize::MAX as u64 {
         return Err(error_out_of_range_length());
     }
 
@@ -1,24 +1,35 @@
-use std::io::Read;
-
-use std::fs::File;
-use std::marker::PhantomData;
-use std::path::Path;
-
-use crate::circular_buffer::CircularBuffer;
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+#[cfg(feature = "std")]
+use {
+    crate::{circular_buffer::CircularBuffer, limits::reset_limits, Error},
+    alloc::vec::Vec,
+    std::{fs::File, io::Read, path::Path},
+};
+
+#[cfg(feature = "tokio")]
+use crate::async_io::{AsyncReader, ReadFromAsyncStream};
 use crate::context::{Context, DefaultContext};
 use crate::endianness::Endianness;
+use crate::limits::track_bytes_read;
 use crate::reader::Reader;
-use crate::Error;
 
-use crate::error::{error_end_of_input, error_input_buffer_is_too_small};
+use crate::error::{error_at_offset, error_end_of_input, error_input_buffer_is_too_small};
 
-struct BufferReader<'a, C>
+/// A reader which reads from an in-memory buffer.
+///
+/// Any borrowed data (e.g. `Cow<str>` or `Cow<[u8]>`) read through it
+/// will be borrowed directly from the underlying buffer instead of being copied.
+pub struct BufferReader<'a, C>
 where
     C: Context,
 {
     context: C,
+    start: *const u8,
     ptr: *const u8,
     end: *const u8,
+    depth: usize,
     phantom: PhantomData<&'a [u8]>,
 }
 
@@ -27,14 +38,34 @@ where
     C: Context,
 {
     #[inline]
-    fn new(context: C, buffer: &'a [u8]) -> Self {
+    pub fn new(context: C, buffer: &'a [u8]) -> Self {
         BufferReader {
             context,
+            start: buffer.as_ptr(),
             ptr: buffer.as_ptr(),
             end: unsafe { buffer.as_ptr().add(buffer.len()) },
+            depth: 0,
             phantom: PhantomData,
         }
     }
+
+    /// Returns the number of bytes which were already read from the buffer.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.ptr as usize - self.start as usize
+    }
+
+    /// Returns the part of the buffer which wasn't read yet.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        unsafe { core::slice::from_raw_parts(self.ptr, self.end as usize - self.ptr as usize) }
+    }
+
+    /// Checks whether the whole buffer was already read.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ptr == self.end
+    }
 }
 
 impl<'a, C: Context> Reader<'a, C> for BufferReader<'a, C> {
@@ -45,8 +76,9 @@ impl<'a, C: Context> Reader<'a, C> for BufferReader<'a, C> {
             return Err(error_end_of_input());
         }
 
+        track_bytes_read(&mut self.context, length)?;
         unsafe {
-            std::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
+            core::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
             self.ptr = self.ptr.add(length);
         }
 
@@ -61,7 +93,7 @@ impl<'a, C: Context> Reader<'a, C> for BufferReader<'a, C> {
         }
 
         unsafe {
-            std::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
+            core::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
         }
 
         Ok(())
@@ -73,6 +105,7 @@ impl<'a, C: Context> Reader<'a, C> for BufferReader<'a, C> {
             return Err(error_end_of_input());
         }
 
+        track_bytes_read(&mut self.context, length)?;
         unsafe {
             self.ptr = self.ptr.add(length);
         }
@@ -85,9 +118,13 @@ impl<'a, C: Context> Reader<'a, C> for BufferReader<'a, C> {
             return Some(Err(error_end_of_input()));
         }
 
+        if let Err(error) = track_bytes_read(&mut self.context, length) {
+            return Some(Err(error));
+        }
+
         let slice;
         unsafe {
-            slice = std::slice::from_raw_parts(self.ptr, length);
+            slice = core::slice::from_raw_parts(self.ptr, length);
             self.ptr = self.ptr.add(length);
         }
 
@@ -108,6 +145,11 @@ impl<'a, C: Context> Reader<'a, C> for BufferReader<'a, C> {
     fn context_mut(&mut self) -> &mut C {
         &mut self.context
     }
+
+    #[inline(always)]
+    fn nesting_depth_mut(&mut self) -> Option<&mut usize> {
+        Some(&mut self.depth)
+    }
 }
 
 struct CopyingBufferReader<'a, C>
@@ -115,8 +157,10 @@ where
     C: Context,
 {
     context: C,
+    start: *const u8,
     ptr: *const u8,
     end: *const u8,
+    depth: usize,
     phantom: PhantomData<&'a [u8]>,
 }
 
@@ -128,11 +172,18 @@ where
     fn new(context: C, buffer: &'a [u8]) -> Self {
         CopyingBufferReader {
             context,
+            start: buffer.as_ptr(),
             ptr: buffer.as_ptr(),
             end: unsafe { buffer.as_ptr().add(buffer.len()) },
+            depth: 0,
             phantom: PhantomData,
         }
     }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.ptr as usize - self.start as usize
+    }
 }
 
 impl<'r, 'a, C: Context> Reader<'r, C> for CopyingBufferReader<'a, C> {
@@ -143,8 +194,9 @@ impl<'r, 'a, C: Context> Reader<'r, C> for CopyingBufferReader<'a, C> {
             return Err(error_end_of_input());
         }
 
+        track_bytes_read(&mut self.context, length)?;
         unsafe {
-            std::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
+            core::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
             self.ptr = self.ptr.add(length);
         }
 
@@ -159,7 +211,7 @@ impl<'r, 'a, C: Context> Reader<'r, C> for CopyingBufferReader<'a, C> {
         }
 
         unsafe {
-            std::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
+            core::ptr::copy_nonoverlapping(self.ptr, output.as_mut_ptr(), length);
         }
 
         Ok(())
@@ -171,6 +223,7 @@ impl<'r, 'a, C: Context> Reader<'r, C> for CopyingBufferReader<'a, C> {
             return Err(error_end_of_input());
         }
 
+        track_bytes_read(&mut self.context, length)?;
         unsafe {
             self.ptr = self.ptr.add(length);
         }
@@ -191,15 +244,24 @@ impl<'r, 'a, C: Context> Reader<'r, C> for CopyingBufferReader<'a, C> {
     fn context_mut(&mut self) -> &mut C {
         &mut self.context
     }
+
+    #[inline(always)]
+    fn nesting_depth_mut(&mut self) -> Option<&mut usize> {
+        Some(&mut self.depth)
+    }
 }
 
-struct StreamReader<C: Context, S: Read> {
+#[cfg(feature = "std")]
+pub(crate) struct StreamReader<C: Context, S: Read> {
     context: C,
     reader: S,
     buffer: CircularBuffer,
     is_buffering: bool,
+    position: usize,
+    depth: usize,
 }
 
+#[cfg(feature = "std")]
 impl<'a, C, S> StreamReader<C, S>
 where
     C: Context,
@@ -235,7 +297,7 @@ where
         }
 
         if self.buffer.len() > 0 {
-            let length = std::cmp::min(self.buffer.len(), output.len());
+            let length = core::cmp::min(self.buffer.len(), output.len());
             self.buffer.consume_into(&mut output[..length]);
             output = &mut output[length..];
         }
@@ -251,15 +313,20 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, C: Context, S: Read> Reader<'a, C> for StreamReader<C, S> {
     #[inline(always)]
     fn read_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error> {
+        track_bytes_read(&mut self.context, output.len())?;
         if self.buffer.len() >= output.len() {
             self.buffer.consume_into(output);
+            self.position += output.len();
             return Ok(());
         }
 
-        self.read_bytes_slow(output)
+        self.read_bytes_slow(output)?;
+        self.position += output.len();
+        Ok(())
     }
 
     fn peek_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error> {
@@ -269,7 +336,7 @@ impl<'a, C: Context, S: Read> Reader<'a, C> for StreamReader<C, S> {
                 let mut chunk_size = output.len() - self.buffer.len();
                 if self.is_buffering {
                     chunk_size =
-                        std::cmp::max(chunk_size, self.buffer.capacity() - self.buffer.len());
+                        core::cmp::max(chunk_size, self.buffer.capacity() - self.buffer.len());
                 }
 
                 let bytes_written = self
@@ -305,28 +372,382 @@ impl<'a, C: Context, S: Read> Reader<'a, C> for StreamReader<C, S> {
     fn context_mut(&mut self) -> &mut C {
         &mut self.context
     }
+
+    #[inline(always)]
+    fn nesting_depth_mut(&mut self) -> Option<&mut usize> {
+        Some(&mut self.depth)
+    }
 }
 
+/// An iterator over consecutive values deserialized from a buffer.
+///
+/// Iteration stops after the first error.
+pub struct BufferIter<'a, C: Context, T> {
+    reader: BufferReader<'a, C>,
+    is_finished: bool,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, C: Context, T> BufferIter<'a, C, T> {
+    /// Returns the number of bytes which were already read from the buffer.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.reader.position()
+    }
+
+    /// Returns the part of the buffer which wasn't read yet.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.reader.remaining()
+    }
+}
+
+impl<'a, C, T> Iterator for BufferIter<'a, C, T>
+where
+    C: Context,
+    T: Readable<'a, C>,
+{
+    type Item = Result<T, C::Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished || self.reader.is_empty() {
+            return None;
+        }
+
+        let bytes_needed = T::minimum_bytes_needed();
+        let buffer_length = self.reader.remaining().len();
+        let result = if buffer_length < bytes_needed {
+            Err(error_input_buffer_is_too_small(buffer_length, bytes_needed))
+        } else {
+            T::read_from(&mut self.reader)
+                .map_err(|error| error_at_offset(error, self.reader.position()))
+        };
+
+        if result.is_err() {
+            self.is_finished = true;
+        }
+
+        Some(result)
+    }
+}
+
+impl<'a, C, T> core::iter::FusedIterator for BufferIter<'a, C, T>
+where
+    C: Context,
+    T: Readable<'a, C>,
+{
+}
+
+/// A reader over data which might be only partially available.
+///
+/// Instead of just failing when it runs out of data it keeps track of
+/// how many more bytes would be necessary to satisfy the biggest request
+/// which it couldn't fulfill.
+pub(crate) struct PartialReader<'r, C: Context> {
+    context: &'r mut C,
+    first: &'r [u8],
+    second: &'r [u8],
+    position: usize,
+    bytes_missing: Cell<usize>,
+    depth: usize,
+}
+
+impl<'r, C: Context> PartialReader<'r, C> {
+    #[inline]
+    pub(crate) fn new(context: &'r mut C, first: &'r [u8], second: &'r [u8]) -> Self {
+        PartialReader {
+            context,
+            first,
+            second,
+            position: 0,
+            bytes_missing: Cell::new(0),
+            depth: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns how many more bytes past the end of the data were requested.
+    #[inline]
+    pub(crate) fn bytes_missing(&self) -> usize {
+        self.bytes_missing.get()
+    }
+
+    #[inline]
+    fn copy_into(&self, output: &mut [u8]) {
+        let mut position = self.position;
+        let mut output = output;
+        if position < self.first.len() {
+            let length = core::cmp::min(self.first.len() - position, output.len());
+            output[..length].copy_from_slice(&self.first[position..position + length]);
+            output = &mut output[length..];
+            position += length;
+        }
+
+        if !output.is_empty() {
+            let position = position - self.first.len();
+            output.copy_from_slice(&self.second[position..position + output.len()]);
+        }
+    }
+}
+
+impl<'a, 'r, C: Context> Reader<'a, C> for PartialReader<'r, C> {
+    #[inline]
+    fn read_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error> {
+        if self.can_read_at_least(output.len()) == Some(false) {
+            return Err(error_end_of_input());
+        }
+
+        track_bytes_read(self.context, output.len())?;
+        self.copy_into(output);
+        self.position += output.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn peek_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error> {
+        if self.can_read_at_least(output.len()) == Some(false) {
+            return Err(error_end_of_input());
+        }
+
+        self.copy_into(output);
+        Ok(())
+    }
+
+    #[inline]
+    fn skip_bytes(&mut self, length: usize) -> Result<(), C::Error> {
+        if self.can_read_at_least(length) == Some(false) {
+            return Err(error_end_of_input());
+        }
+
+        track_bytes_read(self.context, length)?;
+        self.position += length;
+        Ok(())
+    }
+
+    #[inline]
+    fn can_read_at_least(&self, size: usize) -> Option<bool> {
+        let available = self.first.len() + self.second.len() - self.position;
+        if size > available {
+            let missing = size - available;
+            if missing > self.bytes_missing.get() {
+                self.bytes_missing.set(missing);
+            }
+
+            return Some(false);
+        }
+
+        Some(true)
+    }
+
+    #[inline(always)]
+    fn context(&self) -> &C {
+        self.context
+    }
+
+    #[inline(always)]
+    fn context_mut(&mut self) -> &mut C {
+        self.context
+    }
+
+    #[inline(always)]
+    fn nesting_depth_mut(&mut self) -> Option<&mut usize> {
+        Some(&mut self.depth)
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) const DEFAULT_STREAM_BUFFER_CAPACITY: usize = 8 * 1024;
+
+#[cfg(feature = "std")]
 impl<C: Context, S: Read> StreamReader<C, S> {
+    #[inline]
+    pub(crate) fn new(context: C, reader: S, capacity: usize) -> Self {
+        StreamReader {
+            context,
+            reader,
+            buffer: CircularBuffer::with_capacity(capacity),
+            is_buffering: capacity > 0,
+            position: 0,
+            depth: 0,
+        }
+    }
+
     #[inline]
     fn deserialize<'a, T: Readable<'a, C>>(
         context: C,
         reader: S,
         is_buffering: bool,
     ) -> Result<T, C::Error> {
-        let capacity = if is_buffering { 8 * 1024 } else { 0 };
-
-        let mut reader = StreamReader {
-            context,
-            reader,
-            buffer: CircularBuffer::with_capacity(capacity),
-            is_buffering,
+        let capacity = if is_buffering {
+            DEFAULT_STREAM_BUFFER_CAPACITY
+        } else {
+            0
         };
 
-        T::read_from(&mut reader)
+        let mut reader = StreamReader::new(context, reader, capacity);
+        T::read_from(&mut reader).map_err(|error| error_at_offset(error, reader.position))
+    }
+
+    /// Checks whether the underlying stream is exhausted, buffering
+    /// at least one byte from it if it isn't.
+    pub(crate) fn is_at_end(&mut self) -> Result<bool, C::Error> {
+        if self.buffer.len() > 0 {
+            return Ok(false);
+        }
+
+        let reader = &mut self.reader;
+        let chunk_size = core::cmp::max(self.buffer.capacity(), 1);
+        let bytes_written = self
+            .buffer
+            .try_append_with(chunk_size, |chunk| reader.read(chunk))
+            .map_err(|error| {
+                let error = Error::from_io_error(error);
+                <C::Error as From<Error>>::from(error)
+            })?;
+
+        Ok(bytes_written == 0)
+    }
+
+    pub(crate) fn into_inner(self) -> (S, Vec<u8>) {
+        (self.reader, self.buffer.to_vec())
+    }
+}
+
+/// Deserializes multiple consecutive values from a single stream.
+///
+/// Unlike [`read_from_stream_buffered`](Readable::read_from_stream_buffered)
+/// the internal buffer is kept around between values, so no data is lost
+/// when the stream contains more than one serialized value.
+#[cfg(feature = "std")]
+pub struct StreamDeserializer<C: Context, S: Read> {
+    reader: StreamReader<C, S>,
+}
+
+#[cfg(feature = "std")]
+impl<C: Context, S: Read> StreamDeserializer<C, S> {
+    /// Creates a new deserializer with a default internal buffer size.
+    #[inline]
+    pub fn new(context: C, stream: S) -> Self {
+        Self::with_capacity(context, stream, DEFAULT_STREAM_BUFFER_CAPACITY)
+    }
+
+    /// Creates a new deserializer which will read from the stream in chunks of `capacity` bytes.
+    #[inline]
+    pub fn with_capacity(context: C, stream: S, capacity: usize) -> Self {
+        StreamDeserializer {
+            reader: StreamReader::new(context, stream, core::cmp::max(capacity, 1)),
+        }
+    }
+
+    /// Reads the next value from the stream.
+    ///
+    /// Returns `None` if the stream ended cleanly right before the next value.
+    /// If the stream ends in the middle of a value an end-of-input error is returned.
+    #[inline]
+    pub fn read_next<'a, T: Readable<'a, C>>(&mut self) -> Option<Result<T, C::Error>> {
+        match self.reader.is_at_end() {
+            Ok(true) => None,
+            Ok(false) => {
+                reset_limits(&mut self.reader.context);
+                let reader = &mut self.reader;
+                Some(T::read_from(reader).map_err(|error| error_at_offset(error, reader.position)))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+
+    /// Checks whether there is no more data left to read.
+    #[inline]
+    pub fn is_at_end(&mut self) -> Result<bool, C::Error> {
+        self.reader.is_at_end()
+    }
+
+    /// Returns an iterator which will deserialize consecutive values of type `T`.
+    #[inline]
+    pub fn into_values<'a, T: Readable<'a, C>>(self) -> StreamIter<'a, C, S, T> {
+        StreamIter {
+            deserializer: self,
+            is_finished: false,
+            phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn context(&self) -> &C {
+        &self.reader.context
+    }
+
+    #[inline]
+    pub fn context_mut(&mut self) -> &mut C {
+        &mut self.reader.context
+    }
+
+    /// Returns the underlying stream along with any data which was
+    /// already read from it but wasn't yet consumed.
+    pub fn into_inner(self) -> (S, Vec<u8>) {
+        self.reader.into_inner()
+    }
+}
+
+/// An iterator over consecutive values deserialized from a stream.
+///
+/// Iteration stops after the first error.
+#[cfg(feature = "std")]
+pub struct StreamIter<'a, C: Context, S: Read, T> {
+    deserializer: StreamDeserializer<C, S>,
+    is_finished: bool,
+    phantom: PhantomData<fn(&'a [u8]) -> T>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, C: Context, S: Read, T> StreamIter<'a, C, S, T> {
+    /// Returns the underlying stream along with any data which was
+    /// already read from it but wasn't yet consumed.
+    #[inline]
+    pub fn into_inner(self) -> (S, Vec<u8>) {
+        self.deserializer.into_inner()
     }
 }
 
+#[cfg(feature = "std")]
+impl<'a, C, S, T> Iterator for StreamIter<'a, C, S, T>
+where
+    C: Context,
+    S: Read,
+    T: Readable<'a, C>,
+{
+    type Item = Result<T, C::Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+
+        let result = self.deserializer.read_next();
+        match result {
+            Some(Ok(_)) => {}
+            _ => self.is_finished = true,
+        }
+
+        result
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, C, S, T> core::iter::FusedIterator for StreamIter<'a, C, S, T>
+where
+    C: Context,
+    S: Read,
+    T: Readable<'a, C>,
+{
+}
+
 pub trait Readable<'a, C: Context>: Sized {
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error>;
 
@@ -369,6 +790,7 @@ pub trait Readable<'a, C: Context>: Sized {
     ///
     /// Use [`read_from_stream_buffered`](Readable::read_from_stream_buffered) if you need
     /// to read from a stream and you don't care about not overreading.
+    #[cfg(feature = "std")]
     #[inline]
     fn read_from_stream_unbuffered(stream: impl Read) -> Result<Self, C::Error>
     where
@@ -386,6 +808,7 @@ pub trait Readable<'a, C: Context>: Sized {
     ///
     /// Use the slower [`read_from_stream_unbuffered`](Readable::read_from_stream_unbuffered) if you want
     /// to avoid overreading.
+    #[cfg(feature = "std")]
     #[inline]
     fn read_from_stream_buffered(stream: impl Read) -> Result<Self, C::Error>
     where
@@ -395,6 +818,46 @@ pub trait Readable<'a, C: Context>: Sized {
         Self::read_from_stream_buffered_with_ctx(Default::default(), stream)
     }
 
+    /// Asynchronously reads from a given stream with internal buffering.
+    ///
+    /// Just as [`read_from_stream_buffered`](Readable::read_from_stream_buffered) this
+    /// can read more data from the stream than is necessary to deserialize a given type.
+    /// Use an [`AsyncStreamDeserializer`](crate::AsyncStreamDeserializer) if you want
+    /// to read multiple values from the same stream.
+    #[cfg(feature = "tokio")]
+    #[inline]
+    fn read_from_async_stream<S: AsyncReader>(stream: S) -> ReadFromAsyncStream<'a, C, S, Self>
+    where
+        Self: DefaultContext<Context = C>,
+        C: Default,
+    {
+        Self::read_from_async_stream_with_ctx(Default::default(), stream)
+    }
+
+    /// Returns an iterator which deserializes consecutive values from a given buffer.
+    #[inline]
+    fn iter_from_buffer(buffer: &'a [u8]) -> BufferIter<'a, C, Self>
+    where
+        Self: DefaultContext<Context = C>,
+        C: Default,
+    {
+        Self::iter_from_buffer_with_ctx(Default::default(), buffer)
+    }
+
+    /// Returns an iterator which deserializes consecutive values from a given stream.
+    ///
+    /// The stream is read with internal buffering which is preserved between values.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn iter_from_stream<S: Read>(stream: S) -> StreamIter<'a, C, S, Self>
+    where
+        Self: DefaultContext<Context = C>,
+        C: Default,
+    {
+        Self::iter_from_stream_with_ctx(Default::default(), stream)
+    }
+
+    #[cfg(feature = "std")]
     #[inline]
     fn read_from_file(path: impl AsRef<Path>) -> Result<Self, C::Error>
     where
@@ -424,9 +887,9 @@ pub trait Readable<'a, C: Context>: Sized {
         }
 
         let mut reader = BufferReader::new(context, buffer);
-        let value = Self::read_from(&mut reader);
-        let bytes_read = reader.ptr as usize - buffer.as_ptr() as usize;
-        (value, bytes_read)
+        let value =
+            Self::read_from(&mut reader).map_err(|error| error_at_offset(error, reader.position()));
+        (value, reader.position())
     }
 
     #[inline]
@@ -438,9 +901,10 @@ pub trait Readable<'a, C: Context>: Sized {
         }
 
         let mut reader = CopyingBufferReader::new(context, buffer);
-        Self::read_from(&mut reader)
+        Self::read_from(&mut reader).map_err(|error| error_at_offset(error, reader.position()))
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     fn read_from_stream_unbuffered_with_ctx<S: Read>(
         context: C,
@@ -449,6 +913,7 @@ pub trait Readable<'a, C: Context>: Sized {
         StreamReader::deserialize(context, stream, false)
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     fn read_from_stream_buffered_with_ctx<S: Read>(
         context: C,
@@ -457,6 +922,31 @@ pub trait Readable<'a, C: Context>: Sized {
         StreamReader::deserialize(context, stream, true)
     }
 
+    #[cfg(feature = "tokio")]
+    #[inline]
+    fn read_from_async_stream_with_ctx<S: AsyncReader>(
+        context: C,
+        stream: S,
+    ) -> ReadFromAsyncStream<'a, C, S, Self> {
+        ReadFromAsyncStream::new(context, stream)
+    }
+
+    #[inline]
+    fn iter_from_buffer_with_ctx(context: C, buffer: &'a [u8]) -> BufferIter<'a, C, Self> {
+        BufferIter {
+            reader: BufferReader::new(context, buffer),
+            is_finished: false,
+            phantom: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn iter_from_stream_with_ctx<S: Read>(context: C, stream: S) -> StreamIter<'a, C, S, Self> {
+        StreamDeserializer::new(context, stream).into_values()
+    }
+
+    #[cfg(feature = "std")]
     #[inline]
     fn read_from_file_with_ctx(context: C, path: impl AsRef<Path>) -> Result<Self, C::Error> {
         let stream = File::open(path).map_err(|error| {
@@ -496,7 +986,7 @@ pub trait Readable<'a, C: Context>: Sized {
 #[test]
 fn test_peek() {
     let value: f64 = 2.0;
-    let data = unsafe { std::slice::from_raw_parts((&value as *const f64) as *const u8, 8) };
+    let data = unsafe { core::slice::from_raw_parts((&value as *const f64) as *const u8, 8) };
 
     let mut reader = CopyingBufferReader::new(crate::LittleEndian {}, data);
     assert_eq!(reader.peek_f64().unwrap(), reader.read_f64().unwrap());
@@ -1,9 +1,27 @@
-use std::borrow::{Cow, ToOwned};
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::hash::{BuildHasher, Hash};
-use std::mem;
-use std::num::NonZeroU32;
-use std::ops::Range;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::cmp::Reverse;
+use core::marker::PhantomData;
+use core::mem;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+use core::ops::{Bound, Range, RangeInclusive};
+
+#[cfg(feature = "std")]
+use {
+    core::hash::{BuildHasher, Hash},
+    std::collections::{HashMap, HashSet},
+    std::sync::{Mutex, RwLock},
+};
 
 use crate::readable::Readable;
 use crate::reader::Reader;
@@ -21,7 +39,7 @@ where
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let length = crate::private::read_length(reader)?;
-        reader.read_collection(length)
+        crate::private::read_nested(reader, |reader| reader.read_collection(length))
     }
 
     #[inline]
@@ -38,7 +56,7 @@ where
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let length = crate::private::read_length(reader)?;
-        reader.read_collection(length)
+        crate::private::read_nested(reader, |reader| reader.read_collection(length))
     }
 
     #[inline]
@@ -47,6 +65,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, C, K, V, S> Readable<'a, C> for HashMap<K, V, S>
 where
     C: Context,
@@ -57,7 +76,7 @@ where
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let length = crate::private::read_length(reader)?;
-        reader.read_collection(length)
+        crate::private::read_nested(reader, |reader| reader.read_collection(length))
     }
 
     #[inline]
@@ -66,6 +85,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, C, T, S> Readable<'a, C> for HashSet<T, S>
 where
     C: Context,
@@ -75,7 +95,50 @@ where
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let length = crate::private::read_length(reader)?;
-        reader.read_collection(length)
+        crate::private::read_nested(reader, |reader| reader.read_collection(length))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        4
+    }
+}
+
+impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for VecDeque<T> {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let value: Vec<T> = reader.read_value()?;
+        Ok(value.into())
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        <Vec<T> as Readable<'a, C>>::minimum_bytes_needed()
+    }
+}
+
+impl<'a, C, T> Readable<'a, C> for BinaryHeap<T>
+where
+    C: Context,
+    T: Readable<'a, C> + Ord,
+{
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let value: Vec<T> = reader.read_value()?;
+        Ok(value.into())
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        <Vec<T> as Readable<'a, C>>::minimum_bytes_needed()
+    }
+}
+
+impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for LinkedList<T> {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let length = crate::private::read_length(reader)?;
+        crate::private::read_nested(reader, |reader| reader.read_collection(length))
     }
 
     #[inline]
@@ -105,7 +168,7 @@ impl<'a, C: Context> Readable<'a, C> for char {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let value = reader.read_u32()?;
-        std::char::from_u32(value).ok_or_else(crate::error::error_out_of_range_char)
+        core::char::from_u32(value).ok_or_else(crate::error::error_out_of_range_char)
     }
 
     #[inline]
@@ -142,7 +205,7 @@ macro_rules! impl_for_primitive {
             #[doc(hidden)]
             #[inline]
             unsafe fn speedy_slice_from_bytes(slice: &[u8]) -> &[Self] {
-                std::slice::from_raw_parts(
+                core::slice::from_raw_parts(
                     slice.as_ptr() as *const $type,
                     slice.len() / mem::size_of::<Self>(),
                 )
@@ -161,6 +224,7 @@ impl_for_primitive!(i8, read_i8, swap_slice_i8);
 impl_for_primitive!(i16, read_i16, swap_slice_i16);
 impl_for_primitive!(i32, read_i32, swap_slice_i32);
 impl_for_primitive!(i64, read_i64, swap_slice_i64);
+impl_for_primitive!(i128, read_i128, swap_slice_i128);
 impl_for_primitive!(u8, read_u8, swap_slice_u8);
 impl_for_primitive!(u16, read_u16, swap_slice_u16);
 impl_for_primitive!(u32, read_u32, swap_slice_u32);
@@ -174,7 +238,7 @@ impl<'a, C: Context> Readable<'a, C> for usize {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let value = u64::read_from(reader)?;
-        if value > std::usize::MAX as u64 {
+        if value > core::usize::MAX as u64 {
             return Err(crate::error::error_too_big_usize_for_this_architecture());
         }
         Ok(value as usize)
@@ -186,6 +250,22 @@ impl<'a, C: Context> Readable<'a, C> for usize {
     }
 }
 
+impl<'a, C: Context> Readable<'a, C> for isize {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let value = i64::read_from(reader)?;
+        if value > isize::MAX as i64 || value < isize::MIN as i64 {
+            return Err(crate::error::error_too_big_usize_for_this_architecture());
+        }
+        Ok(value as isize)
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        <i64 as Readable<'a, C>>::minimum_bytes_needed()
+    }
+}
+
 impl<'a, C: Context> Readable<'a, C> for String {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
@@ -215,11 +295,76 @@ impl<'a, C: Context> Readable<'a, C> for Cow<'a, str> {
     }
 }
 
+impl<'a, C: Context> Readable<'a, C> for &'a str {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let bytes: &'a [u8] = reader.read_value()?;
+        core::str::from_utf8(bytes).map_err(crate::error::error_invalid_str_utf8)
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        4
+    }
+}
+
+macro_rules! impl_for_borrowed_slice {
+    ($type:ty) => {
+        impl<'a, C: Context> Readable<'a, C> for &'a [$type] {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let length = crate::private::read_length(reader)?;
+                if mem::size_of::<$type>() != 1 && reader.endianness().conversion_necessary() {
+                    return Err(crate::error::error_borrow_needs_endianness_conversion());
+                }
+
+                let byte_length = length
+                    .checked_mul(mem::size_of::<$type>())
+                    .ok_or_else(crate::error::error_out_of_range_length)?;
+
+                if byte_length == 0 {
+                    return Ok(&[]);
+                }
+
+                let bytes = match reader.read_bytes_borrowed(byte_length) {
+                    Some(bytes) => bytes?,
+                    None => return Err(crate::error::error_input_is_not_borrowable()),
+                };
+
+                if bytes.as_ptr() as usize % mem::align_of::<$type>() != 0 {
+                    return Err(crate::error::error_unaligned_borrow());
+                }
+
+                Ok(unsafe { <$type as Readable<'a, C>>::speedy_slice_from_bytes(bytes) })
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                4
+            }
+        }
+    };
+}
+
+impl_for_borrowed_slice!(i8);
+impl_for_borrowed_slice!(i16);
+impl_for_borrowed_slice!(i32);
+impl_for_borrowed_slice!(i64);
+impl_for_borrowed_slice!(i128);
+impl_for_borrowed_slice!(u8);
+impl_for_borrowed_slice!(u16);
+impl_for_borrowed_slice!(u32);
+impl_for_borrowed_slice!(u64);
+impl_for_borrowed_slice!(u128);
+impl_for_borrowed_slice!(f32);
+impl_for_borrowed_slice!(f64);
+impl_for_borrowed_slice!(half::f16);
+
 impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for Vec<T> {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let length = crate::private::read_length(reader)?;
-        reader.read_vec(length)
+        crate::private::read_nested(reader, |reader| reader.read_vec(length))
     }
 
     #[inline]
@@ -244,6 +389,55 @@ where
     }
 }
 
+macro_rules! impl_for_pointer {
+    ($type:ident) => {
+        impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for $type<T> {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: T = reader.read_value()?;
+                Ok($type::new(value))
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                // Forwarding this to `T` would recurse forever for recursive types.
+                0
+            }
+        }
+
+        impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for $type<[T]> {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: Vec<T> = reader.read_value()?;
+                Ok(value.into())
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                <Vec<T> as Readable<'a, C>>::minimum_bytes_needed()
+            }
+        }
+
+        impl<'a, C: Context> Readable<'a, C> for $type<str> {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: String = reader.read_value()?;
+                Ok(value.into())
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                <String as Readable<'a, C>>::minimum_bytes_needed()
+            }
+        }
+    };
+}
+
+impl_for_pointer!(Box);
+impl_for_pointer!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+impl_for_pointer!(Arc);
+
 impl<'a, C: Context, T: Readable<'a, C>, V: Readable<'a, C>> Readable<'a, C> for Result<T, V> {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
@@ -277,12 +471,86 @@ impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for Range<T> {
     }
 }
 
+impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for RangeInclusive<T> {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let start = reader.read_value()?;
+        let end = reader.read_value()?;
+        Ok(start..=end)
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        <T as Readable<'a, C>>::minimum_bytes_needed() * 2
+    }
+}
+
+impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for Bound<T> {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let tag = reader.read_u8()?;
+        match tag {
+            0 => Ok(Bound::Included(reader.read_value()?)),
+            1 => Ok(Bound::Excluded(reader.read_value()?)),
+            2 => Ok(Bound::Unbounded),
+            _ => Err(crate::error::error_invalid_enum_variant_tag(
+                "Bound", tag as u64,
+            )),
+        }
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        1
+    }
+}
+
+macro_rules! impl_for_wrapper {
+    ($type:ident, $constructor:expr) => {
+        impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for $type<T> {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: T = reader.read_value()?;
+                Ok($constructor(value))
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                <T as Readable<'a, C>>::minimum_bytes_needed()
+            }
+        }
+    };
+}
+
+impl_for_wrapper!(Cell, Cell::new);
+impl_for_wrapper!(RefCell, RefCell::new);
+impl_for_wrapper!(Wrapping, Wrapping);
+impl_for_wrapper!(Reverse, Reverse);
+#[cfg(feature = "std")]
+impl_for_wrapper!(Mutex, Mutex::new);
+#[cfg(feature = "std")]
+impl_for_wrapper!(RwLock, RwLock::new);
+
+impl<'a, C: Context, T: ?Sized> Readable<'a, C> for PhantomData<T> {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(_: &mut R) -> Result<Self, C::Error> {
+        Ok(PhantomData)
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        0
+    }
+}
+
 impl<'a, C: Context, T: Readable<'a, C>> Readable<'a, C> for Option<T> {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let flag = reader.read_value()?;
         let value = if flag {
-            Some(reader.read_value()?)
+            Some(crate::private::read_nested(reader, |reader| {
+                reader.read_value()
+            })?)
         } else {
             None
         };
@@ -362,22 +630,40 @@ impl<'a, C: Context> Readable<'a, C> for Endianness {
     }
 }
 
-impl<'a, C: Context> Readable<'a, C> for NonZeroU32 {
-    #[inline]
-    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
-        let value = reader.read_u32()?;
-        NonZeroU32::new(value).ok_or_else(crate::error::error_zero_non_zero)
-    }
+macro_rules! impl_for_non_zero {
+    ($type:ident, $base_type:ty) => {
+        impl<'a, C: Context> Readable<'a, C> for $type {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: $base_type = reader.read_value()?;
+                $type::new(value).ok_or_else(crate::error::error_zero_non_zero)
+            }
 
-    #[inline]
-    fn minimum_bytes_needed() -> usize {
-        4
-    }
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                <$base_type as Readable<'a, C>>::minimum_bytes_needed()
+            }
+        }
+    };
 }
 
+impl_for_non_zero!(NonZeroI8, i8);
+impl_for_non_zero!(NonZeroI16, i16);
+impl_for_non_zero!(NonZeroI32, i32);
+impl_for_non_zero!(NonZeroI64, i64);
+impl_for_non_zero!(NonZeroI128, i128);
+impl_for_non_zero!(NonZeroIsize, isize);
+
+impl_for_non_zero!(NonZeroU8, u8);
+impl_for_non_zero!(NonZeroU16, u16);
+impl_for_non_zero!(NonZeroU32, u32);
+impl_for_non_zero!(NonZeroU64, u64);
+impl_for_non_zero!(NonZeroU128, u128);
+impl_for_non_zero!(NonZeroUsize, usize);
+
 macro_rules! impl_for_atomic {
     ($type:ident, $base_type:ty) => {
-        impl<'a, C: Context> Readable<'a, C> for std::sync::atomic::$type {
+        impl<'a, C: Context> Readable<'a, C> for core::sync::atomic::$type {
             #[inline(always)]
             fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
                 let value: $base_type = reader.read_value()?;
@@ -386,23 +672,32 @@ macro_rules! impl_for_atomic {
 
             #[inline]
             fn minimum_bytes_needed() -> usize {
-                mem::size_of::<$base_type>()
+                <$base_type as Readable<'a, C>>::minimum_bytes_needed()
             }
         }
     };
 }
 
+impl_for_atomic!(AtomicBool, bool);
+
 impl_for_atomic!(AtomicI8, i8);
 impl_for_atomic!(AtomicI16, i16);
 impl_for_atomic!(AtomicI32, i32);
+#[cfg(target_has_atomic = "64")]
 impl_for_atomic!(AtomicI64, i64);
 
 impl_for_atomic!(AtomicU8, u8);
 impl_for_atomic!(AtomicU16, u16);
 impl_for_atomic!(AtomicU32, u32);
+#[cfg(target_has_atomic = "64")]
 impl_for_atomic!(AtomicU64, u64);
 
-impl<'a, C> Readable<'a, C> for std::net::Ipv4Addr
+#[cfg(target_has_atomic = "ptr")]
+impl_for_atomic!(AtomicIsize, isize);
+#[cfg(target_has_atomic = "ptr")]
+impl_for_atomic!(AtomicUsize, usize);
+
+impl<'a, C> Readable<'a, C> for core::net::Ipv4Addr
 where
     C: Context,
 {
@@ -418,7 +713,7 @@ where
     }
 }
 
-impl<'a, C> Readable<'a, C> for std::net::Ipv6Addr
+impl<'a, C> Readable<'a, C> for core::net::Ipv6Addr
 where
     C: Context,
 {
@@ -439,7 +734,7 @@ where
     }
 }
 
-impl<'a, C> Readable<'a, C> for std::net::IpAddr
+impl<'a, C> Readable<'a, C> for core::net::IpAddr
 where
     C: Context,
 {
@@ -447,9 +742,12 @@ where
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let kind = reader.read_u8()?;
         match kind {
-            0 => Ok(std::net::IpAddr::V4(reader.read_value()?)),
-            1 => Ok(std::net::IpAddr::V6(reader.read_value()?)),
-            _ => Err(crate::error::error_invalid_enum_variant()),
+            0 => Ok(core::net::IpAddr::V4(reader.read_value()?)),
+            1 => Ok(core::net::IpAddr::V6(reader.read_value()?)),
+            _ => Err(crate::error::error_invalid_enum_variant_tag(
+                "IpAddr",
+                kind as u64,
+            )),
         }
     }
 
@@ -459,7 +757,66 @@ where
     }
 }
 
-impl<'a, C> Readable<'a, C> for std::time::Duration
+impl<'a, C> Readable<'a, C> for core::net::SocketAddrV4
+where
+    C: Context,
+{
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let ip = reader.read_value()?;
+        let port = reader.read_u16()?;
+        Ok(core::net::SocketAddrV4::new(ip, port))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        6
+    }
+}
+
+impl<'a, C> Readable<'a, C> for core::net::SocketAddrV6
+where
+    C: Context,
+{
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let ip = reader.read_value()?;
+        let port = reader.read_u16()?;
+        let flowinfo = reader.read_u32()?;
+        let scope_id = reader.read_u32()?;
+        Ok(core::net::SocketAddrV6::new(ip, port, flowinfo, scope_id))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        26
+    }
+}
+
+impl<'a, C> Readable<'a, C> for core::net::SocketAddr
+where
+    C: Context,
+{
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let kind = reader.read_u8()?;
+        match kind {
+            0 => Ok(core::net::SocketAddr::V4(reader.read_value()?)),
+            1 => Ok(core::net::SocketAddr::V6(reader.read_value()?)),
+            _ => Err(crate::error::error_invalid_enum_variant_tag(
+                "SocketAddr",
+                kind as u64,
+            )),
+        }
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        7
+    }
+}
+
+impl<'a, C> Readable<'a, C> for core::time::Duration
 where
     C: Context,
 {
@@ -467,7 +824,7 @@ where
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
         let secs = reader.read_u64()?;
         let nanos = reader.read_u32()?;
-        Ok(std::time::Duration::new(secs, nanos))
+        Ok(core::time::Duration::new(secs, nanos))
     }
 
     #[inline]
@@ -476,13 +833,14 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, C> Readable<'a, C> for std::time::SystemTime
 where
     C: Context,
 {
     #[inline]
     fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
-        let duration = std::time::Duration::read_from(reader)?;
+        let duration = core::time::Duration::read_from(reader)?;
         std::time::SystemTime::UNIX_EPOCH
             .checked_add(duration)
             .ok_or_else(crate::error::error_invalid_system_time)
@@ -494,62 +852,48 @@ where
     }
 }
 
-macro_rules! repeat {
-    (1, $expr:expr) => {
-        [$expr]
-    };
-    (2, $expr:expr) => {
-        [$expr, $expr]
-    };
-    (3, $expr:expr) => {
-        [$expr, $expr, $expr]
-    };
-    (4, $expr:expr) => {
-        [$expr, $expr, $expr, $expr]
-    };
-    (5, $expr:expr) => {
-        [$expr, $expr, $expr, $expr, $expr]
-    };
-    (6, $expr:expr) => {
-        [$expr, $expr, $expr, $expr, $expr, $expr]
-    };
-    (7, $expr:expr) => {
-        [$expr, $expr, $expr, $expr, $expr, $expr, $expr]
-    };
-    (8, $expr:expr) => {
-        [$expr, $expr, $expr, $expr, $expr, $expr, $expr, $expr]
-    };
-}
+impl<'a, C, T, const N: usize> Readable<'a, C> for [T; N]
+where
+    C: Context,
+    T: Readable<'a, C>,
+{
+    #[inline(always)]
+    fn read_from<R>(reader: &mut R) -> Result<Self, C::Error>
+    where
+        R: Reader<'a, C>,
+    {
+        if T::speedy_is_primitive() {
+            // An all-zero bit pattern is a valid value for every primitive type.
+            let mut array: [T; N] = unsafe { mem::MaybeUninit::zeroed().assume_init() };
+            reader.read_bytes(unsafe { T::speedy_slice_as_bytes_mut(&mut array) })?;
+            T::speedy_convert_slice_endianness(reader.endianness(), &mut array);
+            return Ok(array);
+        }
 
-macro_rules! impl_for_array {
-    ($count:tt) => {
-        impl<'a, C, T> Readable<'a, C> for [T; $count]
-        where
-            C: Context,
-            T: Readable<'a, C>,
-        {
-            #[inline(always)]
-            fn read_from<R>(reader: &mut R) -> Result<Self, C::Error>
-            where
-                R: Reader<'a, C>,
-            {
-                let array = repeat!($count, reader.read_value()?);
-                Ok(array)
+        let mut failure = None;
+        let array: [Option<T>; N] = core::array::from_fn(|index| {
+            if failure.is_some() {
+                return None;
             }
 
-            #[inline]
-            fn minimum_bytes_needed() -> usize {
-                T::minimum_bytes_needed() * $count
+            match reader.read_value() {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    failure = Some(crate::error::error_add_index(error, index));
+                    None
+                }
             }
+        });
+
+        if let Some(error) = failure {
+            return Err(error);
         }
-    };
-}
 
-impl_for_array!(1);
-impl_for_array!(2);
-impl_for_array!(3);
-impl_for_array!(4);
-impl_for_array!(5);
-impl_for_array!(6);
-impl_for_array!(7);
-impl_for_array!(8);
+        Ok(array.map(|value| value.unwrap()))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        T::minimum_bytes_needed() * N
+    }
+}
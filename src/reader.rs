@@ -1,13 +1,20 @@
-use std::borrow::Cow;
-use std::iter::FromIterator;
-use std::mem::{self, MaybeUninit};
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::mem::{self, MaybeUninit};
 
 use crate::context::Context;
 use crate::endianness::Endianness;
+use crate::limits::track_collection;
 use crate::readable::Readable;
 use crate::varint::VarInt64;
 
-use crate::error::error_end_of_input;
+use crate::error::{error_add_index, error_end_of_input};
+
+/// The maximum number of bytes which will be preallocated for a collection
+/// if we can't verify that its length is actually sane.
+const MAX_UNVERIFIED_PREALLOCATION: usize = 64 * 1024;
 
 pub trait Reader<'a, C: Context>: Sized {
     fn read_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error>;
@@ -15,6 +22,14 @@ pub trait Reader<'a, C: Context>: Sized {
     fn context(&self) -> &C;
     fn context_mut(&mut self) -> &mut C;
 
+    /// Returns the nesting depth the reader is currently at; used to enforce
+    /// the default depth limit when the context doesn't have its own limits.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn nesting_depth_mut(&mut self) -> Option<&mut usize> {
+        None
+    }
+
     #[inline(always)]
     fn skip_bytes(&mut self, mut length: usize) -> Result<(), C::Error> {
         while length > 0 {
@@ -193,6 +208,11 @@ pub trait Reader<'a, C: Context>: Sized {
         Ok(self.context().endianness().read_u128(&slice))
     }
 
+    #[inline(always)]
+    fn read_i128(&mut self) -> Result<i128, C::Error> {
+        self.read_u128().map(|value| value as i128)
+    }
+
     #[inline(always)]
     fn peek_u64(&mut self) -> Result<u64, C::Error> {
         if self.can_read_at_least(8) == Some(false) {
@@ -285,30 +305,67 @@ pub trait Reader<'a, C: Context>: Sized {
     where
         T: Readable<'a, C>,
     {
+        // This has to be checked before we check whether there's enough input, otherwise
+        // a partial reader would just ask for more data instead of failing right away.
+        track_collection::<C, T>(self.context_mut(), length)?;
+
         let (required, overflow) = T::minimum_bytes_needed().overflowing_mul(length);
-        if overflow || self.can_read_at_least(required) == Some(false) {
+        let is_available = if overflow {
+            Some(false)
+        } else {
+            self.can_read_at_least(required)
+        };
+
+        if is_available == Some(false) {
             return Err(error_end_of_input());
         }
 
+        // If we don't know whether there's enough data in the input then the length
+        // can't be trusted, so we don't preallocate everything in one go.
+        let capacity = if is_available == Some(true) {
+            length
+        } else {
+            let max_length = MAX_UNVERIFIED_PREALLOCATION / core::cmp::max(mem::size_of::<T>(), 1);
+            core::cmp::min(length, core::cmp::max(max_length, 1))
+        };
+
         if T::speedy_is_primitive() {
-            let mut vec = Vec::with_capacity(length);
-            unsafe {
-                vec.set_len(length);
-                self.read_bytes(T::speedy_slice_as_bytes_mut(&mut vec))?;
+            let mut vec: Vec<T> = Vec::with_capacity(capacity);
+            while vec.len() < length {
+                let position = vec.len();
+                let chunk_length = core::cmp::min(length - position, capacity);
+                vec.reserve(chunk_length);
+                unsafe {
+                    vec.set_len(position + chunk_length);
+                    self.read_bytes(T::speedy_slice_as_bytes_mut(&mut vec[position..]))?;
+                }
             }
             T::speedy_convert_slice_endianness(self.endianness(), &mut vec);
             Ok(vec)
-        } else {
+        } else if capacity == length {
             let mut vec: Vec<T> = Vec::with_capacity(length);
-            for _ in 0..length {
-                let value = self.read_value()?;
+            for index in 0..length {
+                let value = match self.read_value() {
+                    Ok(value) => value,
+                    Err(error) => return Err(error_add_index(error, index)),
+                };
 
                 // If we don't do this then for some reason LLVM has trouble
                 // eliding the Vec's realloc.
                 let length = vec.len();
                 unsafe {
                     vec.set_len(length + 1);
-                    std::ptr::write(vec.as_mut_ptr().offset(length as isize), value);
+                    core::ptr::write(vec.as_mut_ptr().offset(length as isize), value);
+                }
+            }
+
+            Ok(vec)
+        } else {
+            let mut vec: Vec<T> = Vec::with_capacity(capacity);
+            for index in 0..length {
+                match self.read_value() {
+                    Ok(value) => vec.push(value),
+                    Err(error) => return Err(error_add_index(error, index)),
                 }
             }
 
@@ -337,7 +394,7 @@ pub trait Reader<'a, C: Context>: Sized {
                     let mut vec: Vec<T> = Vec::with_capacity(length);
                     unsafe {
                         vec.set_len(length);
-                        std::ptr::copy_nonoverlapping(
+                        core::ptr::copy_nonoverlapping(
                             bytes.as_ptr(),
                             vec.as_mut_ptr() as *mut u8,
                             bytes.len(),
@@ -363,9 +420,13 @@ pub trait Reader<'a, C: Context>: Sized {
         U: FromIterator<T>,
         T: Readable<'a, C>,
     {
+        track_collection::<C, T>(self.context_mut(), length)?;
         (0..length)
             .into_iter()
-            .map(|_| self.read_value::<T>())
+            .map(|index| {
+                self.read_value::<T>()
+                    .map_err(|error| error_add_index(error, index))
+            })
             .collect()
     }
 
@@ -381,3 +442,99 @@ pub trait Reader<'a, C: Context>: Sized {
         Ok(value.into())
     }
 }
+
+/// A reader which can only read up to a given number of bytes from another reader.
+///
+/// Trying to read past its end will result in an end of input error,
+/// even if the underlying reader still has more data.
+pub struct BoundedReader<'r, R> {
+    reader: &'r mut R,
+    remaining: usize,
+}
+
+impl<'r, R> BoundedReader<'r, R> {
+    #[inline]
+    pub fn new(reader: &'r mut R, length: usize) -> Self {
+        BoundedReader {
+            reader,
+            remaining: length,
+        }
+    }
+
+    /// Returns the number of bytes which can still be read.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<'a, 'r, C: Context, R: Reader<'a, C>> Reader<'a, C> for BoundedReader<'r, R> {
+    #[inline(always)]
+    fn read_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error> {
+        if output.len() > self.remaining {
+            return Err(error_end_of_input());
+        }
+
+        self.reader.read_bytes(output)?;
+        self.remaining -= output.len();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn peek_bytes(&mut self, output: &mut [u8]) -> Result<(), C::Error> {
+        if output.len() > self.remaining {
+            return Err(error_end_of_input());
+        }
+
+        self.reader.peek_bytes(output)
+    }
+
+    #[inline(always)]
+    fn skip_bytes(&mut self, length: usize) -> Result<(), C::Error> {
+        if length > self.remaining {
+            return Err(error_end_of_input());
+        }
+
+        self.reader.skip_bytes(length)?;
+        self.remaining -= length;
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn can_read_at_least(&self, size: usize) -> Option<bool> {
+        if size > self.remaining {
+            return Some(false);
+        }
+
+        self.reader.can_read_at_least(size)
+    }
+
+    #[inline(always)]
+    fn read_bytes_borrowed(&mut self, length: usize) -> Option<Result<&'a [u8], C::Error>> {
+        if length > self.remaining {
+            return Some(Err(error_end_of_input()));
+        }
+
+        let result = self.reader.read_bytes_borrowed(length);
+        if let Some(Ok(_)) = result {
+            self.remaining -= length;
+        }
+
+        result
+    }
+
+    #[inline(always)]
+    fn nesting_depth_mut(&mut self) -> Option<&mut usize> {
+        self.reader.nesting_depth_mut()
+    }
+
+    #[inline(always)]
+    fn context(&self) -> &C {
+        self.reader.context()
+    }
+
+    #[inline(always)]
+    fn context_mut(&mut self) -> &mut C {
+        self.reader.context_mut()
+    }
+}
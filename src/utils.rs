@@ -1,5 +1,5 @@
-use std::mem;
-use std::slice;
+use core::mem;
+use core::slice;
 
 macro_rules! unsafe_is_length {
     ($expr:expr) => {
@@ -15,7 +15,7 @@ macro_rules! unsafe_is_length {
             // It actually *does* affect optimization in practice
             // allowing LLVM to assume the length won't overflow
             // in certain cases.
-            unsafe { std::hint::unreachable_unchecked() }
+            unsafe { core::hint::unreachable_unchecked() }
         }
     };
 }
@@ -29,6 +29,7 @@ unsafe impl Primitive for i32 {}
 unsafe impl Primitive for u32 {}
 unsafe impl Primitive for i64 {}
 unsafe impl Primitive for u64 {}
+unsafe impl Primitive for i128 {}
 unsafe impl Primitive for u128 {}
 
 unsafe impl Primitive for half::f16 {}
@@ -1,3 +1,6 @@
+use core::convert::TryFrom;
+
+use crate::error::error_out_of_range_varint;
 use crate::{Context, Readable, Reader, Writable, Writer};
 
 // Encoding:
@@ -14,8 +17,29 @@ use crate::{Context, Readable, Reader, Writable, Writer};
 // The first byte always contains the most significant bits. The rest of the bytes are always
 // written in a little endian order.
 //
+// 128-bit integers which fit in 64 bits are written exactly like 64-bit ones. Anything
+// bigger is written as a 0xFF byte followed by eight zero bytes (which no 64-bit integer
+// is ever serialized as, so readers of narrower types reject it as out of range), followed
+// by a byte containing the number of bytes which follow, followed by the value itself
+// in a little endian order.
+//
 // Signed integers are transformed with a zigzag transformation before being serialized.
 
+/// A variable-length encoded unsigned integer.
+///
+/// Small values take up less space; anything below 128 is serialized as a single byte.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+pub struct VarInt<T>(pub T);
+
+/// A variable-length encoded signed integer.
+///
+/// The value is zigzag-encoded before being serialized as a [`VarInt`],
+/// so numbers close to zero take up little space regardless of their sign.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+pub struct ZigZag<T>(pub T);
+
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub struct VarInt64(u64);
@@ -91,7 +115,7 @@ impl From<VarInt64> for i64 {
     #[inline]
     fn from(value: VarInt64) -> Self {
         let value = value.0;
-        ((value >> 1) ^ (value << 15)) as i64
+        ((value >> 1) as i64) ^ -((value & 1) as i64)
     }
 }
 
@@ -108,11 +132,14 @@ macro_rules! impl_read {
                 let mut value: u64 = 0;
                 {
                     let slice = unsafe {
-                        std::slice::from_raw_parts_mut(&mut value as *mut u64 as *mut u8, $count)
+                        core::slice::from_raw_parts_mut(&mut value as *mut u64 as *mut u8, $count)
                     };
                     $reader.$read_bytes(slice)?;
                 }
                 value = value.to_le();
+                if $count == 8 && value == 0 {
+                    return Err(error_out_of_range_varint());
+                }
                 Ok(VarInt64(upper_bits | value))
             }};
         }
@@ -132,7 +159,7 @@ macro_rules! impl_read {
                     unreachable!()
                 } else {
                     unsafe {
-                        std::hint::unreachable_unchecked();
+                        core::hint::unreachable_unchecked();
                     }
                 }
             }
@@ -159,7 +186,41 @@ impl VarInt64 {
         C: Context,
         R: Reader<'a, C>,
     {
-        impl_read!(reader, peek_u8, peek_bytes)
+        let first_byte = reader.peek_u8()?;
+        let length = (!first_byte).leading_zeros() as usize;
+
+        // Peeking always starts at the current position, so the first byte is peeked again.
+        let mut bytes = [0; 9];
+        reader.peek_bytes(&mut bytes[..length + 1])?;
+        let value = VarInt64::from_parts(first_byte, &bytes[1..length + 1]);
+        if length == 8 && value.0 == 0 {
+            return Err(error_out_of_range_varint());
+        }
+
+        Ok(value)
+    }
+
+    #[inline]
+    fn read_rest<'a, C, R>(first_byte: u8, reader: &mut R) -> Result<Self, C::Error>
+    where
+        C: Context,
+        R: Reader<'a, C>,
+    {
+        let length = (!first_byte).leading_zeros() as usize;
+        let mut bytes = [0; 8];
+        reader.read_bytes(&mut bytes[..length])?;
+        Ok(VarInt64::from_parts(first_byte, &bytes[..length]))
+    }
+
+    #[inline]
+    fn from_parts(first_byte: u8, rest: &[u8]) -> Self {
+        let length = rest.len() as u32;
+        let upper_mask = 0b11111111_u64 >> length;
+        let upper_bits = (upper_mask & (first_byte as u64)).wrapping_shl(length * 8);
+
+        let mut bytes = [0; 8];
+        bytes[..rest.len()].copy_from_slice(rest);
+        VarInt64(upper_bits | u64::from_le_bytes(bytes))
     }
 }
 
@@ -210,56 +271,56 @@ impl<C: Context> Writable<C> for VarInt64 {
                 writer.write_u8(0b10000000 | (value >> 8) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 1) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 1) };
                 writer.write_bytes(slice)
             }
             2 => {
                 writer.write_u8(0b11000000 | (value >> 16) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 2) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 2) };
                 writer.write_bytes(slice)
             }
             3 => {
                 writer.write_u8(0b11100000 | (value >> 24) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 3) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 3) };
                 writer.write_bytes(slice)
             }
             4 => {
                 writer.write_u8(0b11110000 | (value >> 32) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 4) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 4) };
                 writer.write_bytes(slice)
             }
             5 => {
                 writer.write_u8(0b11111000 | (value >> 40) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 5) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 5) };
                 writer.write_bytes(slice)
             }
             6 => {
                 writer.write_u8(0b11111100 | (value >> 48) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 6) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 6) };
                 writer.write_bytes(slice)
             }
             7 => {
                 writer.write_u8(0b11111110 | (value >> 56) as u8)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 7) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 7) };
                 writer.write_bytes(slice)
             }
             8 => {
                 writer.write_u8(0b11111111)?;
                 value = value.to_le();
                 let slice =
-                    unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 8) };
+                    unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 8) };
                 writer.write_bytes(slice)
             }
             _ => {
@@ -267,7 +328,7 @@ impl<C: Context> Writable<C> for VarInt64 {
                     unreachable!()
                 } else {
                     unsafe {
-                        std::hint::unreachable_unchecked();
+                        core::hint::unreachable_unchecked();
                     }
                 }
             }
@@ -372,3 +433,397 @@ fn test_varint64_bruteforce() {
         }
     }
 }
+
+macro_rules! impl_varint {
+    ($type:ty) => {
+        impl From<$type> for VarInt<$type> {
+            #[inline]
+            fn from(value: $type) -> Self {
+                VarInt(value)
+            }
+        }
+
+        impl VarInt<$type> {
+            /// Reads a value without consuming it from the reader.
+            #[inline]
+            pub fn peek_from<'a, C, R>(reader: &mut R) -> Result<Self, C::Error>
+            where
+                C: Context,
+                R: Reader<'a, C>,
+            {
+                let value: u64 = VarInt64::peek_from(reader)?.into();
+                <$type>::try_from(value)
+                    .map(VarInt)
+                    .map_err(|_| error_out_of_range_varint())
+            }
+        }
+
+        impl<'a, C: Context> Readable<'a, C> for VarInt<$type> {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: u64 = VarInt64::read_from(reader)?.into();
+                <$type>::try_from(value)
+                    .map(VarInt)
+                    .map_err(|_| error_out_of_range_varint())
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                1
+            }
+        }
+
+        impl<C: Context> Writable<C> for VarInt<$type> {
+            #[inline]
+            fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+                VarInt64(self.0 as u64).write_to(writer)
+            }
+
+            #[inline]
+            fn bytes_needed(&self) -> Result<usize, C::Error> {
+                Writable::<C>::bytes_needed(&VarInt64(self.0 as u64))
+            }
+        }
+    };
+}
+
+impl_varint!(u16);
+impl_varint!(u32);
+impl_varint!(u64);
+impl_varint!(usize);
+
+// The prefix of 128-bit integers which don't fit in 64 bits.
+const WIDE_U128_MARKER: [u8; 9] = [0xFF, 0, 0, 0, 0, 0, 0, 0, 0];
+
+impl From<u128> for VarInt<u128> {
+    #[inline]
+    fn from(value: u128) -> Self {
+        VarInt(value)
+    }
+}
+
+impl VarInt<u128> {
+    /// Reads a value without consuming it from the reader.
+    #[inline]
+    pub fn peek_from<'a, C, R>(reader: &mut R) -> Result<Self, C::Error>
+    where
+        C: Context,
+        R: Reader<'a, C>,
+    {
+        let first_byte = reader.peek_u8()?;
+        if first_byte != 0xFF {
+            let value: u64 = VarInt64::peek_from(reader)?.into();
+            return Ok(VarInt(value as u128));
+        }
+
+        let mut header = [0; 10];
+        reader.peek_bytes(&mut header[..9])?;
+        if header[..9] != WIDE_U128_MARKER {
+            return Ok(VarInt(u128_from_le_slice(&header[1..9])));
+        }
+
+        reader.peek_bytes(&mut header)?;
+        let length = wide_u128_length(header[9])?;
+        let mut bytes = [0; 26];
+        reader.peek_bytes(&mut bytes[..length + 10])?;
+        Ok(VarInt(u128_from_le_slice(&bytes[10..length + 10])))
+    }
+}
+
+#[inline]
+fn u128_from_le_slice(slice: &[u8]) -> u128 {
+    let mut bytes = [0; 16];
+    bytes[..slice.len()].copy_from_slice(slice);
+    u128::from_le_bytes(bytes)
+}
+
+#[inline]
+fn wide_u128_length<E>(length: u8) -> Result<usize, E>
+where
+    E: From<crate::Error>,
+{
+    match length {
+        9..=16 => Ok(length as usize),
+        _ => Err(error_out_of_range_varint()),
+    }
+}
+
+impl<'a, C: Context> Readable<'a, C> for VarInt<u128> {
+    #[inline]
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let first_byte = reader.read_u8()?;
+        if first_byte != 0xFF {
+            let value: u64 = VarInt64::read_rest(first_byte, reader)?.into();
+            return Ok(VarInt(value as u128));
+        }
+
+        let mut bytes = [0; 16];
+        reader.read_bytes(&mut bytes[..8])?;
+        if bytes[..8] != WIDE_U128_MARKER[1..] {
+            return Ok(VarInt(u128_from_le_slice(&bytes[..8])));
+        }
+
+        let length = wide_u128_length(reader.read_u8()?)?;
+        reader.read_bytes(&mut bytes[..length])?;
+        Ok(VarInt(u128_from_le_slice(&bytes[..length])))
+    }
+
+    #[inline]
+    fn minimum_bytes_needed() -> usize {
+        1
+    }
+}
+
+impl<C: Context> Writable<C> for VarInt<u128> {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        if self.0 <= u64::MAX as u128 {
+            return VarInt64(self.0 as u64).write_to(writer);
+        }
+
+        let length = 16 - self.0.leading_zeros() as usize / 8;
+        writer.write_bytes(&WIDE_U128_MARKER)?;
+        writer.write_u8(length as u8)?;
+        writer.write_bytes(&self.0.to_le_bytes()[..length])
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        if self.0 <= u64::MAX as u128 {
+            return Writable::<C>::bytes_needed(&VarInt64(self.0 as u64));
+        }
+
+        Ok(WIDE_U128_MARKER.len() + 1 + 16 - self.0.leading_zeros() as usize / 8)
+    }
+}
+
+macro_rules! impl_zigzag {
+    ($type:ty, $unsigned_type:ty) => {
+        impl From<$type> for ZigZag<$type> {
+            #[inline]
+            fn from(value: $type) -> Self {
+                ZigZag(value)
+            }
+        }
+
+        impl ZigZag<$type> {
+            #[inline]
+            fn encode(self) -> VarInt<$unsigned_type> {
+                let value = self.0;
+                VarInt(((value << 1) ^ (value >> (<$type>::BITS - 1))) as $unsigned_type)
+            }
+
+            #[inline]
+            fn decode(value: VarInt<$unsigned_type>) -> Self {
+                let value = value.0;
+                ZigZag(((value >> 1) as $type) ^ -((value & 1) as $type))
+            }
+
+            /// Reads a value without consuming it from the reader.
+            #[inline]
+            pub fn peek_from<'a, C, R>(reader: &mut R) -> Result<Self, C::Error>
+            where
+                C: Context,
+                R: Reader<'a, C>,
+            {
+                VarInt::<$unsigned_type>::peek_from(reader).map(Self::decode)
+            }
+        }
+
+        impl<'a, C: Context> Readable<'a, C> for ZigZag<$type> {
+            #[inline]
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let value: VarInt<$unsigned_type> = reader.read_value()?;
+                Ok(Self::decode(value))
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                1
+            }
+        }
+
+        impl<C: Context> Writable<C> for ZigZag<$type> {
+            #[inline]
+            fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+                self.encode().write_to(writer)
+            }
+
+            #[inline]
+            fn bytes_needed(&self) -> Result<usize, C::Error> {
+                Writable::<C>::bytes_needed(&self.encode())
+            }
+        }
+    };
+}
+
+impl_zigzag!(i16, u16);
+impl_zigzag!(i32, u32);
+impl_zigzag!(i64, u64);
+impl_zigzag!(i128, u128);
+impl_zigzag!(isize, usize);
+
+#[test]
+fn test_varint_wrappers_bruteforce() {
+    use crate::Endianness;
+
+    fn check<T>(value: T)
+    where
+        T: Copy + PartialEq + core::fmt::Debug,
+        T: for<'a> Readable<'a, Endianness> + Writable<Endianness>,
+    {
+        let serialized = value
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap();
+        assert_eq!(
+            Writable::<Endianness>::bytes_needed(&value).unwrap(),
+            serialized.len()
+        );
+
+        let deserialized =
+            T::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized).unwrap();
+        assert_eq!(deserialized, value);
+
+        let serialized_be = value.write_to_vec_with_ctx(Endianness::BigEndian).unwrap();
+        assert_eq!(serialized_be, serialized);
+    }
+
+    fn check_peek<T>(value: T, peek: fn(&mut crate::BufferReader<Endianness>) -> T)
+    where
+        T: Copy + PartialEq + core::fmt::Debug,
+        T: for<'a> Readable<'a, Endianness> + Writable<Endianness>,
+    {
+        let mut serialized = value
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap();
+        serialized.push(0xAA);
+
+        let mut reader = crate::BufferReader::new(Endianness::LittleEndian, &serialized);
+        assert_eq!(peek(&mut reader), value);
+        assert_eq!(reader.read_value::<T>().unwrap(), value);
+        assert_eq!(reader.read_u8().unwrap(), 0xAA);
+    }
+
+    for n in 0..128 {
+        let values = [
+            1_u128 << n,
+            (1_u128 << n) - 1,
+            ((1_u128 << n) - 1) << 1,
+            (1_u128 << n).wrapping_mul(0b00000101),
+            (1_u128 << n).wrapping_mul(0b10101010),
+        ];
+
+        for &value in &values {
+            check(VarInt(value));
+            check(ZigZag(value as i128));
+            check(ZigZag((value as i128).wrapping_neg()));
+            check_peek(VarInt(value), |reader| {
+                VarInt::<u128>::peek_from(reader).unwrap()
+            });
+            check_peek(ZigZag(value as i128), |reader| {
+                ZigZag::<i128>::peek_from(reader).unwrap()
+            });
+
+            let value = value as u64;
+            check(VarInt(value));
+            check(VarInt(value as u32));
+            check(VarInt(value as u16));
+            check(ZigZag(value as i64));
+            check(ZigZag(value as i32));
+            check(ZigZag(value as i16));
+            check(ZigZag((value as i64).wrapping_neg()));
+            check_peek(VarInt(value), |reader| {
+                VarInt::<u64>::peek_from(reader).unwrap()
+            });
+            check_peek(ZigZag(value as i32), |reader| {
+                ZigZag::<i32>::peek_from(reader).unwrap()
+            });
+        }
+    }
+
+    for &value in &[i64::MIN, i64::MAX, 0, -1, 1] {
+        check(ZigZag(value));
+        assert_eq!(i64::from(VarInt64::from(value)), value);
+    }
+
+    check(ZigZag(i128::MIN));
+    check(ZigZag(i128::MAX));
+}
+
+#[test]
+fn test_zigzag_small_negative_numbers_are_short() {
+    use crate::Endianness;
+
+    assert_eq!(
+        ZigZag(-1_i64)
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap(),
+        [1]
+    );
+    assert_eq!(
+        ZigZag(-64_i32)
+            .write_to_vec_with_ctx(Endianness::LittleEndian)
+            .unwrap(),
+        [127]
+    );
+}
+
+#[test]
+fn test_varint_out_of_range() {
+    use crate::Endianness;
+
+    let serialized = VarInt(0x10000_u32)
+        .write_to_vec_with_ctx(Endianness::LittleEndian)
+        .unwrap();
+    let error = VarInt::<u16>::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized)
+        .unwrap_err();
+    match error.kind() {
+        crate::ErrorKind::OutOfRangeVarInt => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_varint_u128_is_compatible_with_u64() {
+    use crate::Endianness;
+
+    for n in 0..64 {
+        for &value in &[1_u64 << n, (1_u64 << n) - 1, u64::MAX >> n] {
+            let serialized = VarInt(value)
+                .write_to_vec_with_ctx(Endianness::LittleEndian)
+                .unwrap();
+            assert_eq!(
+                VarInt::<u128>::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized)
+                    .unwrap(),
+                VarInt(value as u128)
+            );
+
+            let serialized_u128 = VarInt(value as u128)
+                .write_to_vec_with_ctx(Endianness::LittleEndian)
+                .unwrap();
+            assert_eq!(serialized_u128, serialized);
+            assert_eq!(
+                VarInt::<u64>::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized)
+                    .unwrap(),
+                VarInt(value)
+            );
+        }
+    }
+
+    let serialized = VarInt(1_u128 << 64)
+        .write_to_vec_with_ctx(Endianness::LittleEndian)
+        .unwrap();
+    let error = VarInt::<u64>::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized)
+        .unwrap_err();
+    match error.kind() {
+        crate::ErrorKind::OutOfRangeVarInt => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+
+    let mut reader = crate::BufferReader::new(Endianness::LittleEndian, &serialized);
+    let error = VarInt::<u64>::peek_from(&mut reader).unwrap_err();
+    match error.kind() {
+        crate::ErrorKind::OutOfRangeVarInt => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+}
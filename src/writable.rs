@@ -1,12 +1,20 @@
-use std::io::{self, Write};
-
-use std::fs::File;
-use std::path::Path;
-
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use {
+    crate::Error,
+    std::{
+        fs::File,
+        io::{self, Write},
+        path::Path,
+    },
+};
+
+#[cfg(feature = "tokio")]
+use crate::async_io::{AsyncWriter, WriteToAsyncStream};
 use crate::context::{Context, DefaultContext};
 use crate::endianness::Endianness;
 use crate::writer::Writer;
-use crate::Error;
 
 use crate::error::{error_end_of_output_buffer, error_output_buffer_is_too_small};
 
@@ -48,11 +56,13 @@ impl<'a, C: Context> Writer<C> for BufferCollector<'a, C> {
     }
 }
 
+#[cfg(feature = "std")]
 struct WritingCollector<C: Context, T: Write> {
     context: C,
     writer: T,
 }
 
+#[cfg(feature = "std")]
 impl<C: Context, T: Write> Writer<C> for WritingCollector<C, T> {
     #[inline]
     fn write_bytes(&mut self, slice: &[u8]) -> Result<(), C::Error> {
@@ -73,11 +83,24 @@ impl<C: Context, T: Write> Writer<C> for WritingCollector<C, T> {
     }
 }
 
-struct SizeCalculatorCollector {
+pub struct SizeCalculatorCollector<'c, C> {
     size: usize,
+    context: Option<&'c mut C>,
+}
+
+impl<'c, C> SizeCalculatorCollector<'c, C> {
+    #[inline]
+    pub fn new(context: Option<&'c mut C>) -> Self {
+        SizeCalculatorCollector { size: 0, context }
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
 }
 
-impl<C: Context> Writer<C> for SizeCalculatorCollector {
+impl<'c, C: Context> Writer<C> for SizeCalculatorCollector<'c, C> {
     #[inline]
     fn write_bytes(&mut self, slice: &[u8]) -> Result<(), C::Error> {
         self.size += slice.len();
@@ -114,6 +137,12 @@ impl<C: Context> Writer<C> for SizeCalculatorCollector {
         Ok(())
     }
 
+    #[inline]
+    fn write_zeros(&mut self, count: usize) -> Result<(), C::Error> {
+        self.size += count;
+        Ok(())
+    }
+
     #[inline]
     fn endianness(&self) -> Endianness {
         Endianness::NATIVE
@@ -121,12 +150,129 @@ impl<C: Context> Writer<C> for SizeCalculatorCollector {
 
     #[inline]
     fn context(&self) -> &C {
-        panic!();
+        match self.context {
+            Some(ref context) => context,
+            None => panic!(),
+        }
     }
 
     #[inline]
     fn context_mut(&mut self) -> &mut C {
-        panic!();
+        match self.context {
+            Some(ref mut context) => context,
+            None => panic!(),
+        }
+    }
+
+    #[inline]
+    fn context_mut_if_available(&mut self) -> Option<&mut C> {
+        self.context.as_deref_mut()
+    }
+}
+
+/// Collects everything written into it so that it can be prefixed with its length.
+///
+/// Without a context, which is the case when we're only calculating how many
+/// bytes would be written, the bytes are only counted and not collected.
+#[doc(hidden)]
+pub struct LengthPrefixedCollector<'c, C> {
+    buffer: Vec<u8>,
+    size: usize,
+    context: Option<&'c mut C>,
+}
+
+impl<'c, C> LengthPrefixedCollector<'c, C> {
+    #[inline]
+    pub fn new(context: Option<&'c mut C>) -> Self {
+        LengthPrefixedCollector {
+            buffer: Vec::new(),
+            size: 0,
+            context,
+        }
+    }
+
+    /// Returns the number of bytes written and, unless they were only counted, the bytes themselves.
+    #[inline]
+    pub fn into_parts(self) -> (usize, Option<Vec<u8>>) {
+        match self.context {
+            Some(_) => (self.size, Some(self.buffer)),
+            None => (self.size, None),
+        }
+    }
+}
+
+impl<'c, C: Context> Writer<C> for LengthPrefixedCollector<'c, C> {
+    #[inline]
+    fn write_bytes(&mut self, slice: &[u8]) -> Result<(), C::Error> {
+        if self.context.is_some() {
+            self.buffer.extend_from_slice(slice);
+        }
+
+        self.size += slice.len();
+        Ok(())
+    }
+
+    #[inline]
+    fn write_zeros(&mut self, count: usize) -> Result<(), C::Error> {
+        if self.context.is_some() {
+            self.buffer.resize(self.buffer.len() + count, 0);
+        }
+
+        self.size += count;
+        Ok(())
+    }
+
+    #[inline]
+    fn write_u16(&mut self, mut value: u16) -> Result<(), C::Error> {
+        self.endianness().swap_u16(&mut value);
+        self.write_bytes(&value.to_ne_bytes())
+    }
+
+    #[inline]
+    fn write_u32(&mut self, mut value: u32) -> Result<(), C::Error> {
+        self.endianness().swap_u32(&mut value);
+        self.write_bytes(&value.to_ne_bytes())
+    }
+
+    #[inline]
+    fn write_u64(&mut self, mut value: u64) -> Result<(), C::Error> {
+        self.endianness().swap_u64(&mut value);
+        self.write_bytes(&value.to_ne_bytes())
+    }
+
+    #[inline]
+    fn write_u128(&mut self, mut value: u128) -> Result<(), C::Error> {
+        self.endianness().swap_u128(&mut value);
+        self.write_bytes(&value.to_ne_bytes())
+    }
+
+    #[inline]
+    fn endianness(&self) -> Endianness {
+        match self.context {
+            Some(ref context) => context.endianness(),
+            None => Endianness::NATIVE,
+        }
+    }
+
+    #[inline]
+    fn context(&self) -> &C {
+        match self.context {
+            Some(ref context) => context,
+            None => panic!(),
+        }
+    }
+
+    #[inline]
+    fn context_mut(&mut self) -> &mut C {
+        match self.context {
+            Some(ref mut context) => context,
+            None => panic!(),
+        }
+    }
+
+    #[inline]
+    fn context_mut_if_available(&mut self) -> Option<&mut C> {
+        self.context.as_deref_mut()
     }
 }
 
@@ -151,6 +297,7 @@ pub trait Writable<C: Context> {
         self.write_to_vec_with_ctx(Default::default())
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     fn write_to_stream<S: Write>(&self, stream: S) -> Result<(), C::Error>
     where
@@ -160,6 +307,18 @@ pub trait Writable<C: Context> {
         self.write_to_stream_with_ctx(Default::default(), stream)
     }
 
+    /// Serializes the value and asynchronously writes it into a given stream.
+    #[cfg(feature = "tokio")]
+    #[inline]
+    fn write_to_async_stream<S: AsyncWriter>(&self, stream: S) -> WriteToAsyncStream<C, S>
+    where
+        Self: DefaultContext<Context = C>,
+        C: Default,
+    {
+        self.write_to_async_stream_with_ctx(Default::default(), stream)
+    }
+
+    #[cfg(feature = "std")]
     #[inline]
     fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), C::Error>
     where
@@ -170,8 +329,8 @@ pub trait Writable<C: Context> {
     }
 
     #[inline]
-    fn write_to_buffer_with_ctx(&self, context: C, buffer: &mut [u8]) -> Result<(), C::Error> {
-        let bytes_needed = self.bytes_needed()?;
+    fn write_to_buffer_with_ctx(&self, mut context: C, buffer: &mut [u8]) -> Result<(), C::Error> {
+        let bytes_needed = self.bytes_needed_with_ctx(&mut context)?;
         let buffer_length = buffer.len();
         let buffer = buffer
             .get_mut(0..bytes_needed)
@@ -187,8 +346,8 @@ pub trait Writable<C: Context> {
     }
 
     #[inline]
-    fn write_to_vec_with_ctx(&self, context: C) -> Result<Vec<u8>, C::Error> {
-        let capacity = self.bytes_needed()?;
+    fn write_to_vec_with_ctx(&self, mut context: C) -> Result<Vec<u8>, C::Error> {
+        let capacity = self.bytes_needed_with_ctx(&mut context)?;
         let mut vec = Vec::with_capacity(capacity);
         unsafe {
             vec.set_len(capacity);
@@ -207,10 +366,11 @@ pub trait Writable<C: Context> {
             vec.set_len(position);
         }
 
-        debug_assert_eq!(position, capacity);
+        debug_assert!(position <= capacity);
         Ok(vec)
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     fn write_to_stream_with_ctx<S: Write>(&self, context: C, stream: S) -> Result<(), C::Error> {
         let mut writer = WritingCollector {
@@ -221,6 +381,17 @@ pub trait Writable<C: Context> {
         self.write_to(&mut writer)
     }
 
+    #[cfg(feature = "tokio")]
+    #[inline]
+    fn write_to_async_stream_with_ctx<S: AsyncWriter>(
+        &self,
+        context: C,
+        stream: S,
+    ) -> WriteToAsyncStream<C, S> {
+        WriteToAsyncStream::new(stream, self.write_to_vec_with_ctx(context))
+    }
+
+    #[cfg(feature = "std")]
     #[inline]
     fn write_to_file_with_ctx(&self, context: C, path: impl AsRef<Path>) -> Result<(), C::Error> {
         let stream = File::create(path).map_err(|error| {
@@ -233,10 +404,20 @@ pub trait Writable<C: Context> {
 
     #[inline]
     fn bytes_needed(&self) -> Result<usize, C::Error> {
-        let mut writer = SizeCalculatorCollector { size: 0 };
+        let mut writer = SizeCalculatorCollector::new(None);
 
         self.write_to(&mut writer)?;
-        Ok(writer.size)
+        Ok(writer.size())
+    }
+
+    /// Calculates the number of bytes needed to write the value with the given context.
+    ///
+    /// This is the same as `bytes_needed` unless the size depends on the context,
+    /// e.g. for types with versioned fields, for which `bytes_needed` has to assume
+    /// that every field will be written.
+    #[inline]
+    fn bytes_needed_with_ctx(&self, _context: &mut C) -> Result<usize, C::Error> {
+        self.bytes_needed()
     }
 
     // Since specialization is not stable yet we do it this way.
@@ -1,8 +1,25 @@
-use std::borrow::{Cow, ToOwned};
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
-use std::mem;
-use std::num::NonZeroU32;
-use std::ops::Range;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::cmp::Reverse;
+use core::marker::PhantomData;
+use core::mem;
+
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+use core::ops::{Bound, Range, RangeInclusive};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, PoisonError, RwLock};
 
 use crate::endianness::Endianness;
 use crate::writable::Writable;
@@ -62,6 +79,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<C, K, V, S> Writable<C> for HashMap<K, V, S>
 where
     C: Context,
@@ -87,6 +105,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<C, T, S> Writable<C> for HashSet<T, S>
 where
     C: Context,
@@ -111,6 +130,64 @@ where
     }
 }
 
+impl<C: Context, T: Writable<C>> Writable<C> for VecDeque<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        write_length(self.len(), writer)?;
+        let (first, second) = self.as_slices();
+        writer.write_slice(first)?;
+        writer.write_slice(second)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        unsafe_is_length!(self.len());
+
+        if T::speedy_is_primitive() {
+            return Ok(4 + self.len() * mem::size_of::<T>());
+        }
+
+        let mut sum = 4;
+        for element in self {
+            sum += element.bytes_needed()?;
+        }
+
+        Ok(sum)
+    }
+}
+
+impl<C: Context, T: Writable<C>> Writable<C> for BinaryHeap<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.as_slice().write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Writable::<C>::bytes_needed(self.as_slice())
+    }
+}
+
+impl<C: Context, T: Writable<C>> Writable<C> for LinkedList<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        write_length(self.len(), writer)?;
+        writer.write_collection(self.iter())
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        unsafe_is_length!(self.len());
+
+        let mut count = mem::size_of::<u32>();
+        for value in self {
+            count += value.bytes_needed()?;
+        }
+
+        Ok(count)
+    }
+}
+
 macro_rules! impl_for_primitive {
     ($type:ty, $write_name:ident) => {
         impl<C: Context> Writable<C> for $type {
@@ -146,6 +223,7 @@ impl_for_primitive!(i8, write_i8);
 impl_for_primitive!(i16, write_i16);
 impl_for_primitive!(i32, write_i32);
 impl_for_primitive!(i64, write_i64);
+impl_for_primitive!(i128, write_i128);
 impl_for_primitive!(u8, write_u8);
 impl_for_primitive!(u16, write_u16);
 impl_for_primitive!(u32, write_u32);
@@ -167,6 +245,18 @@ impl<C: Context> Writable<C> for usize {
     }
 }
 
+impl<C: Context> Writable<C> for isize {
+    #[inline]
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        writer.write_i64(*self as i64)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Ok(mem::size_of::<i64>())
+    }
+}
+
 impl<C: Context> Writable<C> for bool {
     #[inline]
     fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
@@ -315,6 +405,146 @@ impl<C: Context, T: Writable<C>> Writable<C> for Range<T> {
     }
 }
 
+impl<C: Context, T: Writable<C>> Writable<C> for RangeInclusive<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.start().write_to(writer)?;
+        self.end().write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Ok(Writable::<C>::bytes_needed(self.start())? + Writable::<C>::bytes_needed(self.end())?)
+    }
+}
+
+impl<C: Context, T: Writable<C>> Writable<C> for Bound<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        match *self {
+            Bound::Included(ref value) => {
+                writer.write_u8(0)?;
+                value.write_to(writer)
+            }
+            Bound::Excluded(ref value) => {
+                writer.write_u8(1)?;
+                value.write_to(writer)
+            }
+            Bound::Unbounded => writer.write_u8(2),
+        }
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        match *self {
+            Bound::Included(ref value) | Bound::Excluded(ref value) => {
+                Ok(1 + value.bytes_needed()?)
+            }
+            Bound::Unbounded => Ok(1),
+        }
+    }
+}
+
+impl<C: Context, T: Writable<C> + Copy> Writable<C> for Cell<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.get().write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Writable::<C>::bytes_needed(&self.get())
+    }
+}
+
+impl<C: Context, T: Writable<C>> Writable<C> for RefCell<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.try_borrow()
+            .map_err(|_| crate::error::error_already_mutably_borrowed())?
+            .write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.try_borrow()
+            .map_err(|_| crate::error::error_already_mutably_borrowed())?
+            .bytes_needed()
+    }
+}
+
+// A poisoned lock still holds a perfectly serializable value,
+// so the poison is ignored here just as the `Debug` impls do.
+#[cfg(feature = "std")]
+impl<C: Context, T: Writable<C>> Writable<C> for Mutex<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .bytes_needed()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C: Context, T: Writable<C>> Writable<C> for RwLock<T> {
+    #[inline]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_to(writer)
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .bytes_needed()
+    }
+}
+
+impl<C: Context, T: Writable<C>> Writable<C> for Wrapping<T> {
+    #[inline(always)]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.0.write_to(writer)
+    }
+
+    #[inline(always)]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.0.bytes_needed()
+    }
+}
+
+impl<C: Context, T: Writable<C>> Writable<C> for Reverse<T> {
+    #[inline(always)]
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.0.write_to(writer)
+    }
+
+    #[inline(always)]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        self.0.bytes_needed()
+    }
+}
+
+impl<C: Context, T: ?Sized> Writable<C> for PhantomData<T> {
+    #[inline(always)]
+    fn write_to<W: ?Sized + Writer<C>>(&self, _: &mut W) -> Result<(), C::Error> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Ok(0)
+    }
+}
+
 impl<C: Context, T: Writable<C>> Writable<C> for Option<T> {
     #[inline]
     fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
@@ -407,7 +637,7 @@ impl<C: Context> Writable<C> for Endianness {
 impl<'a, C, T> Writable<C> for &'a T
 where
     C: Context,
-    T: Writable<C>,
+    T: ?Sized + Writable<C>,
 {
     #[inline(always)]
     fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
@@ -423,7 +653,7 @@ where
 impl<'a, C, T> Writable<C> for &'a mut T
 where
     C: Context,
-    T: Writable<C>,
+    T: ?Sized + Writable<C>,
 {
     #[inline(always)]
     fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
@@ -436,51 +666,94 @@ where
     }
 }
 
-impl<C> Writable<C> for NonZeroU32
-where
-    C: Context,
-{
-    #[inline]
-    fn write_to<W>(&self, writer: &mut W) -> Result<(), C::Error>
-    where
-        W: ?Sized + Writer<C>,
-    {
-        self.get().write_to(writer)
-    }
+macro_rules! impl_for_pointer {
+    ($type:ident) => {
+        impl<C: Context, T: ?Sized + Writable<C>> Writable<C> for $type<T> {
+            #[inline(always)]
+            fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+                (**self).write_to(writer)
+            }
 
-    #[inline]
-    fn bytes_needed(&self) -> Result<usize, C::Error> {
-        Ok(4)
-    }
+            #[inline(always)]
+            fn bytes_needed(&self) -> Result<usize, C::Error> {
+                (**self).bytes_needed()
+            }
+        }
+    };
+}
+
+impl_for_pointer!(Box);
+impl_for_pointer!(Rc);
+#[cfg(target_has_atomic = "ptr")]
+impl_for_pointer!(Arc);
+
+macro_rules! impl_for_non_zero {
+    ($type:ident) => {
+        impl<C: Context> Writable<C> for $type {
+            #[inline]
+            fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+                self.get().write_to(writer)
+            }
+
+            #[inline]
+            fn bytes_needed(&self) -> Result<usize, C::Error> {
+                Writable::<C>::bytes_needed(&self.get())
+            }
+        }
+    };
 }
 
+impl_for_non_zero!(NonZeroI8);
+impl_for_non_zero!(NonZeroI16);
+impl_for_non_zero!(NonZeroI32);
+impl_for_non_zero!(NonZeroI64);
+impl_for_non_zero!(NonZeroI128);
+impl_for_non_zero!(NonZeroIsize);
+
+impl_for_non_zero!(NonZeroU8);
+impl_for_non_zero!(NonZeroU16);
+impl_for_non_zero!(NonZeroU32);
+impl_for_non_zero!(NonZeroU64);
+impl_for_non_zero!(NonZeroU128);
+impl_for_non_zero!(NonZeroUsize);
+
 macro_rules! impl_for_atomic {
     ($type:ident, $base_type:ty) => {
-        impl<C: Context> Writable<C> for std::sync::atomic::$type {
+        impl<C: Context> Writable<C> for core::sync::atomic::$type {
             #[inline(always)]
             fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
-                writer.write_value(&self.load(std::sync::atomic::Ordering::SeqCst))
+                writer.write_value(&self.load(core::sync::atomic::Ordering::SeqCst))
             }
 
             #[inline]
             fn bytes_needed(&self) -> Result<usize, C::Error> {
-                Ok(mem::size_of::<$base_type>())
+                let value: $base_type = self.load(core::sync::atomic::Ordering::SeqCst);
+                Writable::<C>::bytes_needed(&value)
             }
         }
     };
 }
 
+impl_for_atomic!(AtomicBool, bool);
+
 impl_for_atomic!(AtomicI8, i8);
 impl_for_atomic!(AtomicI16, i16);
 impl_for_atomic!(AtomicI32, i32);
+#[cfg(target_has_atomic = "64")]
 impl_for_atomic!(AtomicI64, i64);
 
 impl_for_atomic!(AtomicU8, u8);
 impl_for_atomic!(AtomicU16, u16);
 impl_for_atomic!(AtomicU32, u32);
+#[cfg(target_has_atomic = "64")]
 impl_for_atomic!(AtomicU64, u64);
 
-impl<C> Writable<C> for std::net::Ipv4Addr
+#[cfg(target_has_atomic = "ptr")]
+impl_for_atomic!(AtomicIsize, isize);
+#[cfg(target_has_atomic = "ptr")]
+impl_for_atomic!(AtomicUsize, usize);
+
+impl<C> Writable<C> for core::net::Ipv4Addr
 where
     C: Context,
 {
@@ -499,7 +772,7 @@ where
     }
 }
 
-impl<C> Writable<C> for std::net::Ipv6Addr
+impl<C> Writable<C> for core::net::Ipv6Addr
 where
     C: Context,
 {
@@ -522,7 +795,7 @@ where
     }
 }
 
-impl<C> Writable<C> for std::net::IpAddr
+impl<C> Writable<C> for core::net::IpAddr
 where
     C: Context,
 {
@@ -532,11 +805,11 @@ where
         W: ?Sized + Writer<C>,
     {
         match self {
-            std::net::IpAddr::V4(address) => {
+            core::net::IpAddr::V4(address) => {
                 writer.write_u8(0)?;
                 address.write_to(writer)
             }
-            std::net::IpAddr::V6(address) => {
+            core::net::IpAddr::V6(address) => {
                 writer.write_u8(1)?;
                 address.write_to(writer)
             }
@@ -546,17 +819,91 @@ where
     #[inline]
     fn bytes_needed(&self) -> Result<usize, C::Error> {
         match self {
-            std::net::IpAddr::V4(address) => {
+            core::net::IpAddr::V4(address) => {
                 Writable::<C>::bytes_needed(address).map(|count| count + 1)
             }
-            std::net::IpAddr::V6(address) => {
+            core::net::IpAddr::V6(address) => {
                 Writable::<C>::bytes_needed(address).map(|count| count + 1)
             }
         }
     }
 }
 
-impl<C> Writable<C> for std::time::Duration
+impl<C> Writable<C> for core::net::SocketAddrV4
+where
+    C: Context,
+{
+    #[inline]
+    fn write_to<W>(&self, writer: &mut W) -> Result<(), C::Error>
+    where
+        W: ?Sized + Writer<C>,
+    {
+        self.ip().write_to(writer)?;
+        writer.write_u16(self.port())
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Ok(6)
+    }
+}
+
+impl<C> Writable<C> for core::net::SocketAddrV6
+where
+    C: Context,
+{
+    #[inline]
+    fn write_to<W>(&self, writer: &mut W) -> Result<(), C::Error>
+    where
+        W: ?Sized + Writer<C>,
+    {
+        self.ip().write_to(writer)?;
+        writer.write_u16(self.port())?;
+        writer.write_u32(self.flowinfo())?;
+        writer.write_u32(self.scope_id())
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        Ok(26)
+    }
+}
+
+impl<C> Writable<C> for core::net::SocketAddr
+where
+    C: Context,
+{
+    #[inline]
+    fn write_to<W>(&self, writer: &mut W) -> Result<(), C::Error>
+    where
+        W: ?Sized + Writer<C>,
+    {
+        match self {
+            core::net::SocketAddr::V4(address) => {
+                writer.write_u8(0)?;
+                address.write_to(writer)
+            }
+            core::net::SocketAddr::V6(address) => {
+                writer.write_u8(1)?;
+                address.write_to(writer)
+            }
+        }
+    }
+
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        match self {
+            core::net::SocketAddr::V4(address) => {
+                Writable::<C>::bytes_needed(address).map(|count| count + 1)
+            }
+            core::net::SocketAddr::V6(address) => {
+                Writable::<C>::bytes_needed(address).map(|count| count + 1)
+            }
+        }
+    }
+}
+
+impl<C> Writable<C> for core::time::Duration
 where
     C: Context,
 {
@@ -575,6 +922,7 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<C> Writable<C> for std::time::SystemTime
 where
     C: Context,
@@ -596,41 +944,29 @@ where
     }
 }
 
-macro_rules! impl_for_array {
-    ($count:tt) => {
-        impl<C, T> Writable<C> for [T; $count]
-        where
-            C: Context,
-            T: Writable<C>,
-        {
-            #[inline]
-            fn write_to<W>(&self, writer: &mut W) -> Result<(), C::Error>
-            where
-                W: ?Sized + Writer<C>,
-            {
-                for item in self {
-                    item.write_to(writer)?;
-                }
-                Ok(())
-            }
+impl<C, T, const N: usize> Writable<C> for [T; N]
+where
+    C: Context,
+    T: Writable<C>,
+{
+    #[inline]
+    fn write_to<W>(&self, writer: &mut W) -> Result<(), C::Error>
+    where
+        W: ?Sized + Writer<C>,
+    {
+        writer.write_slice(self)
+    }
 
-            #[inline]
-            fn bytes_needed(&self) -> Result<usize, C::Error> {
-                let mut size = 0;
-                for item in self {
-                    size += Writable::<C>::bytes_needed(item)?;
-                }
-                Ok(size)
-            }
+    #[inline]
+    fn bytes_needed(&self) -> Result<usize, C::Error> {
+        if T::speedy_is_primitive() {
+            return Ok(N * mem::size_of::<T>());
         }
-    };
-}
 
-impl_for_array!(1);
-impl_for_array!(2);
-impl_for_array!(3);
-impl_for_array!(4);
-impl_for_array!(5);
-impl_for_array!(6);
-impl_for_array!(7);
-impl_for_array!(8);
+        let mut size = 0;
+        for item in self {
+            size += Writable::<C>::bytes_needed(item)?;
+        }
+        Ok(size)
+    }
+}
@@ -1,4 +1,4 @@
-use std::mem;
+use core::mem;
 
 use crate::context::Context;
 use crate::endianness::Endianness;
@@ -11,42 +11,65 @@ pub trait Writer<C: Context> {
     fn context(&self) -> &C;
     fn context_mut(&mut self) -> &mut C;
 
+    /// Returns the context, unless this writer doesn't have one, which is
+    /// the case when we're only calculating how many bytes would be written.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn context_mut_if_available(&mut self) -> Option<&mut C> {
+        Some(self.context_mut())
+    }
+
     #[inline(always)]
     fn can_write_at_least(&self, _size: usize) -> Option<bool> {
         None
     }
 
+    /// Writes the given number of zero bytes; used to account for bytes
+    /// which were only counted and not actually collected.
+    #[doc(hidden)]
+    #[inline]
+    fn write_zeros(&mut self, mut count: usize) -> Result<(), C::Error> {
+        const ZEROS: [u8; 64] = [0; 64];
+        while count > 0 {
+            let chunk = core::cmp::min(count, ZEROS.len());
+            self.write_bytes(&ZEROS[..chunk])?;
+            count -= chunk;
+        }
+
+        Ok(())
+    }
+
     #[inline(always)]
     fn write_u8(&mut self, value: u8) -> Result<(), C::Error> {
-        let slice = unsafe { std::slice::from_raw_parts(&value, 1) };
+        let slice = unsafe { core::slice::from_raw_parts(&value, 1) };
         self.write_bytes(slice)
     }
 
     #[inline(always)]
     fn write_u16(&mut self, mut value: u16) -> Result<(), C::Error> {
         self.context().endianness().swap_u16(&mut value);
-        let slice = unsafe { std::slice::from_raw_parts(&value as *const u16 as *const u8, 2) };
+        let slice = unsafe { core::slice::from_raw_parts(&value as *const u16 as *const u8, 2) };
         self.write_bytes(slice)
     }
 
     #[inline(always)]
     fn write_u32(&mut self, mut value: u32) -> Result<(), C::Error> {
         self.context().endianness().swap_u32(&mut value);
-        let slice = unsafe { std::slice::from_raw_parts(&value as *const u32 as *const u8, 4) };
+        let slice = unsafe { core::slice::from_raw_parts(&value as *const u32 as *const u8, 4) };
         self.write_bytes(slice)
     }
 
     #[inline(always)]
     fn write_u64(&mut self, mut value: u64) -> Result<(), C::Error> {
         self.context().endianness().swap_u64(&mut value);
-        let slice = unsafe { std::slice::from_raw_parts(&value as *const u64 as *const u8, 8) };
+        let slice = unsafe { core::slice::from_raw_parts(&value as *const u64 as *const u8, 8) };
         self.write_bytes(slice)
     }
 
     #[inline(always)]
     fn write_u128(&mut self, mut value: u128) -> Result<(), C::Error> {
         self.context().endianness().swap_u128(&mut value);
-        let slice = unsafe { std::slice::from_raw_parts(&value as *const u128 as *const u8, 16) };
+        let slice = unsafe { core::slice::from_raw_parts(&value as *const u128 as *const u8, 16) };
         self.write_bytes(slice)
     }
 
@@ -70,6 +93,11 @@ pub trait Writer<C: Context> {
         self.write_u64(value as u64)
     }
 
+    #[inline(always)]
+    fn write_i128(&mut self, value: i128) -> Result<(), C::Error> {
+        self.write_u128(value as u128)
+    }
+
     #[inline(always)]
     fn write_f32(&mut self, value: f32) -> Result<(), C::Error> {
         let value: u32 = unsafe { mem::transmute(value) };
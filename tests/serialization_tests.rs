@@ -1,11 +1,18 @@
 use std::borrow::Cow;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
 use std::fmt::Debug;
-use std::num::NonZeroU32;
-use std::ops::Range;
+use std::marker::PhantomData;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroIsize, NonZeroU32, NonZeroU64, NonZeroUsize, Wrapping,
+};
+use std::ops::{Bound, Range, RangeInclusive};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
 
 #[allow(unused_imports)]
-use persia_speedy::{Endianness, Readable, Writable};
+use persia_speedy::{Context, Endianness, Readable, Reader, VarInt, Writable, Writer, ZigZag};
 
 macro_rules! symmetric_tests {
     ($(
@@ -274,6 +281,12 @@ struct DerivedRecursiveStruct {
     inner: Vec<DerivedRecursiveStruct>,
 }
 
+#[derive(PartialEq, Debug, Readable, Writable)]
+enum DerivedRecursiveEnum {
+    Leaf(u8),
+    Node(Box<DerivedRecursiveEnum>, Box<DerivedRecursiveEnum>),
+}
+
 #[derive(PartialEq, Debug, Readable, Writable)]
 struct DerivedStructWithVecWithCount {
     length: u8,
@@ -410,6 +423,25 @@ struct DerivedStructWithVecWithLengthTypeVarInt64 {
     data: Vec<u8>,
 }
 
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithBoxedSliceWithLengthTypeU16 {
+    #[speedy(length_type = u16)]
+    data: Box<[u8]>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithBoxedStrWithCount {
+    length: u8,
+    #[speedy(length = length)]
+    data: Box<str>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithVecDequeWithLengthTypeU16 {
+    #[speedy(length_type = u16)]
+    data: VecDeque<u16>,
+}
+
 #[derive(PartialEq, Debug, Readable, Writable)]
 struct DerivedStructWithOptionVecWithLengthTypeU16 {
     #[speedy(length_type = u16)]
@@ -458,6 +490,271 @@ struct DerivedStructWithArray<T> {
     data: [T; 4],
 }
 
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithLongArray {
+    data: [u16; 12],
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithBorrowedFields<'a> {
+    data: &'a [u16],
+    name: &'a str,
+    raw: &'a [u8],
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithVarInts {
+    a: VarInt<u32>,
+    b: ZigZag<i64>,
+    c: VarInt<u128>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithVarIntAttributes {
+    #[speedy(varint)]
+    a: u64,
+    #[speedy(zigzag)]
+    b: i32,
+    #[speedy(varint)]
+    #[speedy(length_type = u8)]
+    c: Vec<u32>,
+    #[speedy(zigzag)]
+    d: Option<i64>,
+    #[speedy(varint)]
+    e: usize,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithCustomDefaults {
+    a: u8,
+    #[speedy(skip, default = a as u32 * 2)]
+    b: u32,
+    #[speedy(default_on_eof, default = 7)]
+    c: u16,
+    #[speedy(default_on_eof, default = vec![a])]
+    d: Vec<u8>,
+}
+
+fn validate_not_empty(values: &Vec<u8>) -> Result<(), String> {
+    if values.is_empty() {
+        return Err("empty".to_owned());
+    }
+
+    Ok(())
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithValidatedCustomDefaults {
+    #[speedy(default_on_eof, default = vec![1])]
+    a: Vec<u8>,
+    #[speedy(default_on_eof, default = Vec::new(), validate = validate_not_empty)]
+    b: Vec<u8>,
+}
+
+fn validate_name(name: &String) -> Result<(), DomainError> {
+    if name.is_empty() {
+        return Err(DomainError { code: 1 });
+    }
+
+    Ok(())
+}
+
+fn validate_struct_with_validation(value: &DerivedStructWithValidation) -> Result<(), String> {
+    if value.items.len() > value.percent as usize {
+        return Err(format!("too many items: {}", value.items.len()));
+    }
+
+    Ok(())
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(validate = validate_struct_with_validation, validate_on_write)]
+struct DerivedStructWithValidation {
+    #[speedy(range = 1..=100)]
+    percent: u8,
+    #[speedy(range = ..10)]
+    limit: Option<i32>,
+    #[speedy(max_length = 4, length_type = u8)]
+    items: Vec<u16>,
+    #[speedy(validate = validate_name, max_length = 8)]
+    name: String,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(validate = validate_struct_with_validation_without_write)]
+struct DerivedStructWithValidationOnlyOnRead {
+    #[speedy(range = 1..=100)]
+    percent: u8,
+}
+
+fn validate_struct_with_validation_without_write(
+    _: &DerivedStructWithValidationOnlyOnRead,
+) -> Result<(), &'static str> {
+    Ok(())
+}
+
+#[derive(PartialEq, Debug, Default)]
+struct NotSpeedy(u32);
+
+mod not_speedy_as_u16 {
+    use super::NotSpeedy;
+    use persia_speedy::{Context, Reader, Writer};
+
+    pub fn read<'a, C: Context, R: Reader<'a, C>>(reader: &mut R) -> Result<NotSpeedy, C::Error> {
+        reader.read_u16().map(|value| NotSpeedy(value.into()))
+    }
+
+    pub fn write<C: Context, W: ?Sized + Writer<C>>(
+        value: &NotSpeedy,
+        writer: &mut W,
+    ) -> Result<(), C::Error> {
+        writer.write_u16(value.0 as u16)
+    }
+
+    pub fn minimum_bytes() -> usize {
+        2
+    }
+}
+
+fn read_u32_as_u8<'a, C: Context, R: Reader<'a, C>>(reader: &mut R) -> Result<u32, C::Error> {
+    reader.read_u8().map(|value| value.into())
+}
+
+fn write_u32_as_u8<C: Context, W: ?Sized + Writer<C>>(
+    value: &u32,
+    writer: &mut W,
+) -> Result<(), C::Error> {
+    writer.write_u8(*value as u8)
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedRecursiveGenericStruct<T> {
+    value: T,
+    children: Vec<DerivedRecursiveGenericStruct<T>>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithSkippedGeneric<T: Default> {
+    value: u8,
+    #[speedy(skip)]
+    cache: T,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+struct DerivedMutuallyRecursiveA<T> {
+    value: T,
+    next: Option<Box<DerivedMutuallyRecursiveB<T>>>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(bound(read = "T: Readable<'a_, C_>", write = "T: Writable<C_>"))]
+struct DerivedMutuallyRecursiveB<T> {
+    items: Vec<DerivedMutuallyRecursiveA<T>>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(bound = "")]
+struct DerivedStructWithEmptyBound<T> {
+    value: u8,
+    #[speedy(skip)]
+    marker: PhantomData<T>,
+}
+
+trait HasId {
+    type Id;
+}
+
+#[derive(PartialEq, Debug)]
+struct Entity;
+
+impl HasId for Entity {
+    type Id = u16;
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithAssociatedType<T: HasId> {
+    id: T::Id,
+    #[speedy(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithAssociatedTypeAndMarker<T: HasId> {
+    id: T::Id,
+    marker: PhantomData<T>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithPhantomData<T> {
+    value: u8,
+    marker: PhantomData<T>,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithGenericCowSlice<'a, T>
+where
+    [T]: ToOwned<Owned = Vec<T>>,
+{
+    values: Cow<'a, [T]>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Byte(u8);
+
+impl From<u8> for Byte {
+    fn from(value: u8) -> Self {
+        Byte(value)
+    }
+}
+
+impl From<Byte> for u8 {
+    fn from(value: Byte) -> Self {
+        value.0
+    }
+}
+
+mod generic_as_u8 {
+    use persia_speedy::{Context, Reader, Writer};
+
+    pub fn read<'a, T, C, R>(reader: &mut R) -> Result<T, C::Error>
+    where
+        T: From<u8>,
+        C: Context,
+        R: Reader<'a, C>,
+    {
+        reader.read_u8().map(T::from)
+    }
+
+    pub fn write<T, C, W>(value: &T, writer: &mut W) -> Result<(), C::Error>
+    where
+        T: Copy + Into<u8>,
+        C: Context,
+        W: ?Sized + Writer<C>,
+    {
+        writer.write_u8((*value).into())
+    }
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithFieldBound<T> {
+    #[speedy(
+        with = generic_as_u8,
+        bound(read = "T: From<u8>", write = "T: Copy + Into<u8>")
+    )]
+    value: T,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithCustomFunctions {
+    #[speedy(with = not_speedy_as_u16, minimum_bytes)]
+    a: NotSpeedy,
+    #[speedy(with = not_speedy_as_u16)]
+    b: NotSpeedy,
+    #[speedy(read_with = read_u32_as_u8, write_with = write_u32_as_u8)]
+    c: u32,
+}
+
 #[derive(PartialEq, Debug, Readable, Writable)]
 struct DerivedStructWithConstantPrefixString {
     #[speedy(constant_prefix = "ABC")]
@@ -516,6 +813,226 @@ enum DerivedEnumWithPeekTagU8 {
     Two(u8),
 }
 
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8)]
+enum DerivedEnumWithUnitOther {
+    A(u8),
+    #[speedy(other)]
+    Unknown,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8)]
+enum DerivedEnumWithTagOther {
+    A(u8),
+    #[speedy(other)]
+    Unknown(u64),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, length_delimited)]
+enum DerivedLengthDelimitedEnum {
+    A(u16),
+    B {
+        value: u8,
+        #[speedy(length_type = u8)]
+        name: String,
+    },
+    #[speedy(other)]
+    Unknown(u64, Vec<u8>),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, length_delimited)]
+enum DerivedLengthDelimitedEnumV2 {
+    A(u16),
+    B {
+        value: u8,
+        #[speedy(length_type = u8)]
+        name: String,
+    },
+    C(u32, bool),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, length_delimited)]
+enum DerivedLengthDelimitedEnumWithExtendedVariant {
+    A(u16, u32),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithLengthDelimitedEnum {
+    value: DerivedLengthDelimitedEnum,
+    tail: u8,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithExtendedLengthDelimitedEnum {
+    value: DerivedLengthDelimitedEnumWithExtendedVariant,
+    tail: u8,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(extensible)]
+struct DerivedExtensibleStruct {
+    a: u8,
+    b: u16,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(extensible)]
+struct DerivedExtensibleStructV2 {
+    a: u8,
+    b: u16,
+    #[speedy(default_on_eof)]
+    c: u32,
+    #[speedy(default_on_eof)]
+    d: Vec<u8>,
+}
+
+thread_local! {
+    static WRITE_COUNT: Cell<usize> = Cell::new(0);
+}
+
+/// A `u8` which counts how many times it was written.
+#[derive(PartialEq, Debug, Readable)]
+struct CountedWrites(u8);
+
+impl<C: Context> Writable<C> for CountedWrites {
+    fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+        WRITE_COUNT.with(|count| count.set(count.get() + 1));
+        writer.write_u8(self.0)
+    }
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(extensible)]
+struct DerivedExtensibleStructWithCountedWrites {
+    value: CountedWrites,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(extensible)]
+struct DerivedNestedExtensibleStruct {
+    value: CountedWrites,
+    inner: DerivedExtensibleStructWithCountedWrites,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(extensible)]
+struct DerivedDoublyNestedExtensibleStruct {
+    value: CountedWrites,
+    inner: DerivedNestedExtensibleStruct,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, length_delimited)]
+enum DerivedLengthDelimitedEnumWithCountedWrites {
+    A(CountedWrites),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, length_delimited)]
+enum DerivedNestedLengthDelimitedEnum {
+    A(CountedWrites, DerivedLengthDelimitedEnumWithCountedWrites),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, length_delimited)]
+enum DerivedDoublyNestedLengthDelimitedEnum {
+    A(CountedWrites, DerivedNestedLengthDelimitedEnum),
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedVersionedStruct {
+    a: u8,
+    #[speedy(since = 2)]
+    b: u16,
+    #[speedy(until = 3)]
+    c: u8,
+    #[speedy(since = 2, until = 4, default = 7)]
+    d: u8,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8, bits)]
+enum DerivedBitsEnum {
+    A,
+    B,
+    #[speedy(tag = 5)]
+    C,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithBits {
+    #[speedy(bits = 1)]
+    flag: bool,
+    #[speedy(bits = 3)]
+    kind: DerivedBitsEnum,
+    #[speedy(bits = 4, range = 0..=10)]
+    level: u8,
+    #[speedy(bits = 5)]
+    offset: i8,
+    value: u16,
+    #[speedy(bits = 12)]
+    wide: u16,
+}
+
+// This one has manual bits impls which map the variants differently than their tags.
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8)]
+enum DerivedEnumWithCustomBits {
+    A,
+    B,
+}
+
+impl persia_speedy::ReadableBits for DerivedEnumWithCustomBits {
+    fn from_bits(bits: u64, _count: u32) -> Result<Self, persia_speedy::Error> {
+        Ok(if bits == 0 {
+            DerivedEnumWithCustomBits::B
+        } else {
+            DerivedEnumWithCustomBits::A
+        })
+    }
+}
+
+impl persia_speedy::WritableBits for DerivedEnumWithCustomBits {
+    fn to_bits(&self, _count: u32) -> Result<u64, persia_speedy::Error> {
+        Ok(match self {
+            DerivedEnumWithCustomBits::A => 1,
+            DerivedEnumWithCustomBits::B => 0,
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithCustomBits {
+    #[speedy(bits = 1)]
+    a: DerivedEnumWithCustomBits,
+    #[speedy(bits = 1)]
+    b: DerivedEnumWithCustomBits,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct DerivedStructWithWideBits {
+    #[speedy(bits = 3)]
+    a: u8,
+    #[speedy(bits = 64)]
+    b: u64,
+    #[speedy(bits = 64)]
+    c: i64,
+    #[speedy(bits = 33)]
+    d: i64,
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(extensible)]
+struct DerivedExtensibleVersionedStruct {
+    values: Vec<DerivedVersionedStruct>,
+    #[speedy(since = 2)]
+    flag: bool,
+}
+
 // This is here only to make sure it compiles.
 #[derive(PartialEq, Debug, Readable, Writable)]
 struct DerivedStructWithTwoDifferentCows<'a> {
@@ -596,6 +1113,9 @@ atomic_wrapper!(AtomicI32, i32);
 atomic_wrapper!(AtomicU32, u32);
 atomic_wrapper!(AtomicI64, i64);
 atomic_wrapper!(AtomicU64, u64);
+atomic_wrapper!(AtomicBool, bool);
+atomic_wrapper!(AtomicIsize, isize);
+atomic_wrapper!(AtomicUsize, usize);
 
 symmetric_tests! {
     vec_u8 for Vec< u8 > {
@@ -810,6 +1330,32 @@ symmetric_tests! {
         be = [0, 0, 0, 0, 0, 0, 0, 33],
         minimum_bytes = 8
     }
+    isize for isize {
+        in = -33,
+        le = [223, 255, 255, 255, 255, 255, 255, 255],
+        be = [255, 255, 255, 255, 255, 255, 255, 223],
+        minimum_bytes = 8
+    }
+    i128 for i128 {
+        in = -33,
+        le = [223, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255],
+        be = [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 223],
+        minimum_bytes = 16
+    }
+    vec_i128 for Vec< i128 > {
+        in = vec![ 1, -1 ],
+        le = [
+            2, 0, 0, 0,
+            1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255
+        ],
+        be = [
+            0, 0, 0, 2,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+            255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255
+        ],
+        minimum_bytes = 4
+    }
     f32 for f32 {
         in = 8388610.0,
         le = [2, 0, 0, 75],
@@ -870,6 +1416,24 @@ symmetric_tests! {
         be = [255, 255, 255, 255, 255, 255, 255, 223],
         minimum_bytes = 8
     }
+    atomic_bool for AtomicBool {
+        in = AtomicBool::new( true ),
+        le = [1],
+        be = [1],
+        minimum_bytes = 1
+    }
+    atomic_isize for AtomicIsize {
+        in = AtomicIsize::new( -33 ),
+        le = [223, 255, 255, 255, 255, 255, 255, 255],
+        be = [255, 255, 255, 255, 255, 255, 255, 223],
+        minimum_bytes = 8
+    }
+    atomic_usize for AtomicUsize {
+        in = AtomicUsize::new( 33 ),
+        le = [33, 0, 0, 0, 0, 0, 0, 0],
+        be = [0, 0, 0, 0, 0, 0, 0, 33],
+        minimum_bytes = 8
+    }
     string for String {
         in = "Hello".to_owned(),
         le = [5, 0, 0, 0, 72, 101, 108, 108, 111],
@@ -882,12 +1446,126 @@ symmetric_tests! {
         be = [0, 0, 0, 5, 72, 101, 108, 108, 111],
         minimum_bytes = 4
     }
-    range_u16 for Range< u16 > {
-        in = 10..11,
-        le = [10, 0, 11, 0],
-        be = [0, 10, 0, 11],
-        minimum_bytes = 4
-    }
+    box_u16 for Box< u16 > {
+        in = Box::new( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 0
+    }
+    box_slice_u16 for Box< [u16] > {
+        in = vec![ 10, 11 ].into_boxed_slice(),
+        le = [2, 0, 0, 0, 10, 0, 11, 0],
+        be = [0, 0, 0, 2, 0, 10, 0, 11],
+        minimum_bytes = 4
+    }
+    box_str for Box< str > {
+        in = "Hello".into(),
+        le = [5, 0, 0, 0, 72, 101, 108, 108, 111],
+        be = [0, 0, 0, 5, 72, 101, 108, 108, 111],
+        minimum_bytes = 4
+    }
+    rc_u16 for Rc< u16 > {
+        in = Rc::new( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 0
+    }
+    rc_slice_u8 for Rc< [u8] > {
+        in = vec![ 10, 11 ].into(),
+        le = [2, 0, 0, 0, 10, 11],
+        be = [0, 0, 0, 2, 10, 11],
+        minimum_bytes = 4
+    }
+    arc_u16 for Arc< u16 > {
+        in = Arc::new( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 0
+    }
+    arc_str for Arc< str > {
+        in = "Hello".into(),
+        le = [5, 0, 0, 0, 72, 101, 108, 108, 111],
+        be = [0, 0, 0, 5, 72, 101, 108, 108, 111],
+        minimum_bytes = 4
+    }
+    range_u16 for Range< u16 > {
+        in = 10..11,
+        le = [10, 0, 11, 0],
+        be = [0, 10, 0, 11],
+        minimum_bytes = 4
+    }
+    range_inclusive_u16 for RangeInclusive< u16 > {
+        in = 10..=11,
+        le = [10, 0, 11, 0],
+        be = [0, 10, 0, 11],
+        minimum_bytes = 4
+    }
+    bound_included_u16 for Bound< u16 > {
+        in = Bound::Included( 10 ),
+        le = [0, 10, 0],
+        be = [0, 0, 10],
+        minimum_bytes = 1
+    }
+    bound_excluded_u16 for Bound< u16 > {
+        in = Bound::Excluded( 10 ),
+        le = [1, 10, 0],
+        be = [1, 0, 10],
+        minimum_bytes = 1
+    }
+    bound_unbounded_u16 for Bound< u16 > {
+        in = Bound::Unbounded,
+        le = [2],
+        be = [2],
+        minimum_bytes = 1
+    }
+    vec_deque_u16 for VecDeque< u16 > {
+        in = {
+            let mut deque = VecDeque::with_capacity( 4 );
+            deque.push_back( 11 );
+            deque.push_back( 12 );
+            deque.push_front( 10 );
+            deque
+        },
+        le = [3, 0, 0, 0, 10, 0, 11, 0, 12, 0],
+        be = [0, 0, 0, 3, 0, 10, 0, 11, 0, 12],
+        minimum_bytes = 4
+    }
+    linked_list_u16 for LinkedList< u16 > {
+        in = vec![ 10, 11 ].into_iter().collect(),
+        le = [2, 0, 0, 0, 10, 0, 11, 0],
+        be = [0, 0, 0, 2, 0, 10, 0, 11],
+        minimum_bytes = 4
+    }
+    cell_u16 for Cell< u16 > {
+        in = Cell::new( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 2
+    }
+    ref_cell_u16 for RefCell< u16 > {
+        in = RefCell::new( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 2
+    }
+    wrapping_u16 for Wrapping< u16 > {
+        in = Wrapping( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 2
+    }
+    reverse_u16 for Reverse< u16 > {
+        in = Reverse( 10 ),
+        le = [10, 0],
+        be = [0, 10],
+        minimum_bytes = 2
+    }
+    phantom_data for PhantomData< u16 > {
+        in = PhantomData,
+        le = [],
+        be = [],
+        minimum_bytes = 0
+    }
     unit for () {
         in = (),
         le = [],
@@ -984,6 +1662,34 @@ symmetric_tests! {
         be = [1, 0x20, 0x01, 0x07, 0x20, 0x15, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 0xa1, 0x00],
         minimum_bytes = 5
     }
+    socket_addr_v4 for std::net::SocketAddrV4 {
+        in = std::net::SocketAddrV4::new( std::net::Ipv4Addr::new( 127, 0, 0, 1 ), 8080 ),
+        le = [1, 0, 0, 127, 0x90, 0x1f],
+        be = [127, 0, 0, 1, 0x1f, 0x90],
+        minimum_bytes = 6
+    }
+    socket_addr_v6 for std::net::SocketAddrV6 {
+        in = std::net::SocketAddrV6::new( std::net::Ipv6Addr::new( 0x2001, 0x720, 0x1500, 0x1, 0, 0, 0, 0xa100 ), 8080, 1, 2 ),
+        le = [
+            0x00, 0xa1, 0, 0, 0, 0, 0, 0, 0x01, 0x00, 0x00, 0x15, 0x20, 0x07, 0x01, 0x20,
+            0x90, 0x1f,
+            1, 0, 0, 0,
+            2, 0, 0, 0
+        ],
+        be = [
+            0x20, 0x01, 0x07, 0x20, 0x15, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 0xa1, 0x00,
+            0x1f, 0x90,
+            0, 0, 0, 1,
+            0, 0, 0, 2
+        ],
+        minimum_bytes = 26
+    }
+    socket_addr_v4_in_socket_addr for std::net::SocketAddr {
+        in = std::net::SocketAddr::V4( std::net::SocketAddrV4::new( std::net::Ipv4Addr::new( 127, 0, 0, 1 ), 8080 ) ),
+        le = [0, 1, 0, 0, 127, 0x90, 0x1f],
+        be = [0, 127, 0, 0, 1, 0x1f, 0x90],
+        minimum_bytes = 7
+    }
     duration for std::time::Duration {
         in = std::time::Duration::new( 1, 2 ),
         le = [
@@ -1146,6 +1852,15 @@ symmetric_tests! {
         be = [0, 0, 0, 1, 0, 0, 0, 0],
         minimum_bytes = 4
     }
+    derived_recursive_enum for DerivedRecursiveEnum {
+        in = DerivedRecursiveEnum::Node(
+            Box::new( DerivedRecursiveEnum::Leaf( 1 ) ),
+            Box::new( DerivedRecursiveEnum::Leaf( 2 ) )
+        ),
+        le = [1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2],
+        be = [0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 2],
+        minimum_bytes = 4
+    }
     derived_struct_with_vec_with_length for DerivedStructWithVecWithCount {
         in = DerivedStructWithVecWithCount { length: 2, data: vec![ true, false, false, true ] },
         le = [2, 1, 0, 0, 1],
@@ -1242,6 +1957,24 @@ symmetric_tests! {
         be = [0, 2, 100, 101],
         minimum_bytes = 2
     }
+    derived_struct_with_boxed_slice_with_length_type_u16 for DerivedStructWithBoxedSliceWithLengthTypeU16 {
+        in = DerivedStructWithBoxedSliceWithLengthTypeU16 { data: vec![ 100, 101 ].into_boxed_slice() },
+        le = [2, 0, 100, 101],
+        be = [0, 2, 100, 101],
+        minimum_bytes = 2
+    }
+    derived_struct_with_boxed_str_with_length for DerivedStructWithBoxedStrWithCount {
+        in = DerivedStructWithBoxedStrWithCount { length: 2, data: "AB".into() },
+        le = [2, b'A', b'B'],
+        be = [2, b'A', b'B'],
+        minimum_bytes = 1
+    }
+    derived_struct_with_vec_deque_with_length_type_u16 for DerivedStructWithVecDequeWithLengthTypeU16 {
+        in = DerivedStructWithVecDequeWithLengthTypeU16 { data: vec![ 100, 101 ].into() },
+        le = [2, 0, 100, 0, 101, 0],
+        be = [0, 2, 0, 100, 0, 101],
+        minimum_bytes = 2
+    }
     derived_struct_with_vec_with_length_type_u32 for DerivedStructWithVecWithLengthTypeU32 {
         in = DerivedStructWithVecWithLengthTypeU32 { data: vec![ 100, 101 ] },
         le = [2, 0, 0, 0, 100, 101],
@@ -1296,12 +2029,138 @@ symmetric_tests! {
         be = [1, 2, 3, 4],
         minimum_bytes = 4
     }
+    derived_struct_with_long_array for DerivedStructWithLongArray {
+        in = DerivedStructWithLongArray { data: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12] },
+        le = [1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0, 7, 0, 8, 0, 9, 0, 10, 0, 11, 0, 12, 0],
+        be = [0, 1, 0, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0, 7, 0, 8, 0, 9, 0, 10, 0, 11, 0, 12],
+        minimum_bytes = 24
+    }
+    array_u8_32 for [u8; 32] {
+        in = [7; 32],
+        le = [7; 32],
+        be = [7; 32],
+        minimum_bytes = 32
+    }
+    array_u16_3 for [u16; 3] {
+        in = [1, 2, 3],
+        le = [1, 0, 2, 0, 3, 0],
+        be = [0, 1, 0, 2, 0, 3],
+        minimum_bytes = 6
+    }
+    array_f32_64 for [f32; 64] {
+        in = [1.0; 64],
+        le = [[0, 0, 0x80, 0x3f]; 64].concat(),
+        be = [[0x3f, 0x80, 0, 0]; 64].concat(),
+        minimum_bytes = 256
+    }
+    array_u16_0 for [u16; 0] {
+        in = [],
+        le = [],
+        be = [],
+        minimum_bytes = 0
+    }
+    array_newtype for [Newtype; 2] {
+        in = [Newtype( 1 ), Newtype( 2 )],
+        le = [1, 0, 2, 0],
+        be = [0, 1, 0, 2],
+        minimum_bytes = 4
+    }
+    derived_struct_with_varints for DerivedStructWithVarInts {
+        in = DerivedStructWithVarInts { a: VarInt( 0x80 ), b: ZigZag( -2 ), c: VarInt( 1 << 64 ) },
+        le = [0x80, 0x80, 3, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        be = [0x80, 0x80, 3, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        minimum_bytes = 3
+    }
+    derived_struct_with_varint_attributes for DerivedStructWithVarIntAttributes {
+        in = DerivedStructWithVarIntAttributes { a: 0x80, b: -1, c: vec![ 1, 300 ], d: Some( -3 ), e: 5 },
+        le = [0x80, 0x80, 1, 2, 1, 0x81, 0x2C, 1, 5, 5],
+        be = [0x80, 0x80, 1, 2, 1, 0x81, 0x2C, 1, 5, 5],
+        minimum_bytes = 5
+    }
+    derived_struct_with_custom_defaults for DerivedStructWithCustomDefaults {
+        in = DerivedStructWithCustomDefaults { a: 2, b: 4, c: 1, d: vec![ 3 ] },
+        le = [2, 1, 0, 1, 0, 0, 0, 3],
+        be = [2, 0, 1, 0, 0, 0, 1, 3],
+        minimum_bytes = 1
+    }
+    derived_struct_with_validation for DerivedStructWithValidation {
+        in = DerivedStructWithValidation { percent: 2, limit: Some( -1 ), items: vec![ 1, 2 ], name: "ab".to_owned() },
+        le = [2, 1, 0xFF, 0xFF, 0xFF, 0xFF, 2, 1, 0, 2, 0, 2, 0, 0, 0, 0x61, 0x62],
+        be = [2, 1, 0xFF, 0xFF, 0xFF, 0xFF, 2, 0, 1, 0, 2, 0, 0, 0, 2, 0x61, 0x62],
+        minimum_bytes = 7
+    }
+    derived_recursive_generic_struct for DerivedRecursiveGenericStruct< u8 > {
+        in = DerivedRecursiveGenericStruct { value: 1, children: vec![ DerivedRecursiveGenericStruct { value: 2, children: vec![] } ] },
+        le = [1, 1, 0, 0, 0, 2, 0, 0, 0, 0],
+        be = [1, 0, 0, 0, 1, 2, 0, 0, 0, 0],
+        minimum_bytes = 5
+    }
+    derived_mutually_recursive for DerivedMutuallyRecursiveA< u8 > {
+        in = DerivedMutuallyRecursiveA { value: 1, next: Some( Box::new( DerivedMutuallyRecursiveB { items: vec![ DerivedMutuallyRecursiveA { value: 2, next: None } ] } ) ) },
+        le = [1, 1, 1, 0, 0, 0, 2, 0],
+        be = [1, 1, 0, 0, 0, 1, 2, 0],
+        minimum_bytes = 2
+    }
+    derived_struct_with_field_bound for DerivedStructWithFieldBound< Byte > {
+        in = DerivedStructWithFieldBound { value: Byte( 7 ) },
+        le = [7],
+        be = [7],
+        minimum_bytes = 0
+    }
+    derived_struct_with_associated_type for DerivedStructWithAssociatedType< Entity > {
+        in = DerivedStructWithAssociatedType { id: 0x1234, marker: PhantomData },
+        le = [0x34, 0x12],
+        be = [0x12, 0x34],
+        minimum_bytes = 2
+    }
+    derived_struct_with_generic_cow_slice for DerivedStructWithGenericCowSlice< u16 > {
+        in = DerivedStructWithGenericCowSlice { values: Cow::Owned( vec![ 1, 2 ] ) },
+        le = [2, 0, 0, 0, 1, 0, 2, 0],
+        be = [0, 0, 0, 2, 0, 1, 0, 2],
+        minimum_bytes = 4
+    }
+    derived_struct_with_custom_functions for DerivedStructWithCustomFunctions {
+        in = DerivedStructWithCustomFunctions { a: NotSpeedy( 1 ), b: NotSpeedy( 2 ), c: 3 },
+        le = [1, 0, 2, 0, 3],
+        be = [0, 1, 0, 2, 3],
+        minimum_bytes = 2
+    }
     non_zero_u32 for NonZeroU32 {
         in = NonZeroU32::new( 33 ).unwrap(),
         le = [33, 0, 0, 0],
         be = [0, 0, 0, 33],
         minimum_bytes = 4
     }
+    non_zero_u64 for NonZeroU64 {
+        in = NonZeroU64::new( 33 ).unwrap(),
+        le = [33, 0, 0, 0, 0, 0, 0, 0],
+        be = [0, 0, 0, 0, 0, 0, 0, 33],
+        minimum_bytes = 8
+    }
+    non_zero_usize for NonZeroUsize {
+        in = NonZeroUsize::new( 33 ).unwrap(),
+        le = [33, 0, 0, 0, 0, 0, 0, 0],
+        be = [0, 0, 0, 0, 0, 0, 0, 33],
+        minimum_bytes = 8
+    }
+    non_zero_i16 for NonZeroI16 {
+        in = NonZeroI16::new( -2 ).unwrap(),
+        le = [254, 255],
+        be = [255, 254],
+        minimum_bytes = 2
+    }
+    non_zero_isize for NonZeroIsize {
+        in = NonZeroIsize::new( -33 ).unwrap(),
+        le = [223, 255, 255, 255, 255, 255, 255, 255],
+        be = [255, 255, 255, 255, 255, 255, 255, 223],
+        minimum_bytes = 8
+    }
+    non_zero_i128 for NonZeroI128 {
+        in = NonZeroI128::new( 33 ).unwrap(),
+        le = [33, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        be = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 33],
+        minimum_bytes = 16
+    }
     derived_struct_with_skipped_field for DerivedStructWithSkippedField {
         in =  DerivedStructWithSkippedField { a: 1, _b: 0, c: 3 },
         le = [1, 3],
@@ -1362,6 +2221,55 @@ symmetric_tests! {
         be = [1],
         minimum_bytes = 1
     }
+    derived_enum_with_unit_other for DerivedEnumWithUnitOther {
+        in = DerivedEnumWithUnitOther::Unknown,
+        le = [1],
+        be = [1],
+        minimum_bytes = 1
+    }
+    derived_enum_with_tag_other for DerivedEnumWithTagOther {
+        in = DerivedEnumWithTagOther::Unknown( 7 ),
+        le = [7],
+        be = [7],
+        minimum_bytes = 1
+    }
+    derived_length_delimited_enum_a for DerivedLengthDelimitedEnum {
+        in = DerivedLengthDelimitedEnum::A( 0x1234 ),
+        le = [0, 2, 0, 0, 0, 0x34, 0x12],
+        be = [0, 0, 0, 0, 2, 0x12, 0x34],
+        minimum_bytes = 5
+    }
+    derived_length_delimited_enum_b for DerivedLengthDelimitedEnum {
+        in = DerivedLengthDelimitedEnum::B { value: 1, name: "ab".to_owned() },
+        le = [1, 4, 0, 0, 0, 1, 2, b'a', b'b'],
+        be = [1, 0, 0, 0, 4, 1, 2, b'a', b'b'],
+        minimum_bytes = 5
+    }
+    derived_length_delimited_enum_unknown for DerivedLengthDelimitedEnum {
+        in = DerivedLengthDelimitedEnum::Unknown( 5, vec![ 1, 2, 3 ] ),
+        le = [5, 3, 0, 0, 0, 1, 2, 3],
+        be = [5, 0, 0, 0, 3, 1, 2, 3],
+        minimum_bytes = 5
+    }
+    derived_struct_with_bits for DerivedStructWithBits {
+        in = DerivedStructWithBits {
+            flag: true,
+            kind: DerivedBitsEnum::C,
+            level: 9,
+            offset: -3,
+            value: 0x1234,
+            wide: 0xABC
+        },
+        le = [0x9B, 0x1D, 0x34, 0x12, 0xBC, 0x0A],
+        be = [0xD9, 0xE8, 0x12, 0x34, 0xAB, 0xC0],
+        minimum_bytes = 6
+    }
+    derived_extensible_struct for DerivedExtensibleStruct {
+        in = DerivedExtensibleStruct { a: 1, b: 0x0203 },
+        le = [3, 0, 0, 0, 1, 3, 2],
+        be = [0, 0, 0, 3, 1, 2, 3],
+        minimum_bytes = 7
+    }
 }
 
 #[cfg(feature = "hashbrown")]
@@ -1517,6 +2425,72 @@ fn test_derived_struct_with_default_on_eof() {
     );
 }
 
+#[test]
+fn test_derived_struct_with_custom_defaults() {
+    use persia_speedy::{Endianness, Readable};
+
+    let deserialized: DerivedStructWithCustomDefaults =
+        Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &[5]).unwrap();
+    assert_eq!(
+        deserialized,
+        DerivedStructWithCustomDefaults {
+            a: 5,
+            b: 10,
+            c: 7,
+            d: vec![5]
+        }
+    );
+
+    let deserialized: DerivedStructWithCustomDefaults =
+        Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &[5, 1, 0, 2, 0]).unwrap();
+    assert_eq!(
+        deserialized,
+        DerivedStructWithCustomDefaults {
+            a: 5,
+            b: 10,
+            c: 1,
+            d: vec![5]
+        }
+    );
+
+    let deserialized: DerivedStructWithCustomDefaults =
+        Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &[5, 1, 0, 2, 0, 0, 0, 8])
+            .unwrap();
+    assert_eq!(
+        deserialized,
+        DerivedStructWithCustomDefaults {
+            a: 5,
+            b: 10,
+            c: 1,
+            d: vec![5]
+        }
+    );
+}
+
+#[test]
+fn test_derived_struct_with_validated_custom_defaults() {
+    let deserialized =
+        DerivedStructWithValidatedCustomDefaults::read_from_buffer(&[1, 0, 0, 0, 2, 1, 0, 0, 0, 3])
+            .unwrap();
+    assert_eq!(
+        deserialized,
+        DerivedStructWithValidatedCustomDefaults {
+            a: vec![2],
+            b: vec![3]
+        }
+    );
+
+    // The defaults are validated like any other value.
+    let error = DerivedStructWithValidatedCustomDefaults::read_from_buffer(&[]).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::ValidationFailed {
+            field_name: Some("b"),
+            ..
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
 #[test]
 fn test_length_mismatch_with_length_attribute() {
     use persia_speedy::{Endianness, Writable};
@@ -1535,45 +2509,1418 @@ fn test_length_mismatch_with_length_attribute() {
 }
 
 #[test]
-fn test_zero_non_zero() {
-    let error = NonZeroU32::read_from_buffer(&[0, 0, 0, 0]).unwrap_err();
-    match persia_speedy::private::get_error_kind(&error) {
-        persia_speedy::private::ErrorKind::ZeroNonZero => {}
+fn test_validation_on_read() {
+    fn read(data: &[u8]) -> persia_speedy::Error {
+        DerivedStructWithValidation::read_from_buffer_with_ctx(Endianness::LittleEndian, data)
+            .unwrap_err()
+    }
+
+    let error = read(&[0, 0, 0, 0, 0, 0, 0]);
+    match error.kind() {
+        persia_speedy::ErrorKind::ValueIsNotInRangeAttribute {
+            field_name: "percent",
+        } => {}
         error => panic!("Unexpected error: {:?}", error),
     }
-}
+    assert_eq!(
+        error.field_path().as_deref(),
+        Some("DerivedStructWithValidation.percent")
+    );
 
-#[test]
-fn test_vec_with_length_type_u7_read_out_of_range_length() {
-    let error = DerivedStructWithVecWithLengthTypeU7::read_from_buffer(&[0x80]).unwrap_err();
-    match persia_speedy::private::get_error_kind(&error) {
-        persia_speedy::private::ErrorKind::OutOfRangeLength => {}
+    let error = read(&[1, 1, 10, 0, 0, 0, 0, 0, 0, 0, 0]);
+    match error.kind() {
+        persia_speedy::ErrorKind::ValueIsNotInRangeAttribute {
+            field_name: "limit",
+        } => {}
         error => panic!("Unexpected error: {:?}", error),
     }
+
+    // The length is checked before anything is allocated.
+    let error = read(&[1, 0, 5, 0, 0, 0, 0]);
+    match error.kind() {
+        persia_speedy::ErrorKind::LengthIsAboveMaxLengthAttribute {
+            field_name: "items",
+            length: 5,
+            max_length: 4,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = read(&[1, 0, 0, 0, 0, 0, 0]);
+    match error.kind() {
+        persia_speedy::ErrorKind::ValidationFailed {
+            field_name: Some("name"),
+            ..
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert_eq!(error.downcast_ref::<DomainError>().unwrap().code, 1);
+
+    let error = read(&[1, 0, 2, 1, 0, 2, 0, 1, 0, 0, 0, 0x61]);
+    match error.kind() {
+        persia_speedy::ErrorKind::ValidationFailed {
+            field_name: None, ..
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert!(error
+        .to_string()
+        .starts_with("validation failed: too many items: 2"));
 }
 
 #[test]
-fn test_prefix_constant_mismatch() {
-    let error = DerivedStructWithConstantPrefixString::read_from_buffer(&[0x41, 0x42]).unwrap_err();
-    match persia_speedy::private::get_error_kind(&error) {
-        persia_speedy::private::ErrorKind::InputBufferIsTooSmall { .. } => {}
+fn test_validation_on_write() {
+    let value = DerivedStructWithValidation {
+        percent: 101,
+        limit: None,
+        items: vec![],
+        name: "a".to_owned(),
+    };
+    let error = value.write_to_vec().unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::ValueIsNotInRangeAttribute {
+            field_name: "percent",
+        } => {}
         error => panic!("Unexpected error: {:?}", error),
     }
 
-    let error =
-        DerivedStructWithConstantPrefixString::read_from_buffer(&[0x41, 0x42, 0x00]).unwrap_err();
-    match persia_speedy::private::get_error_kind(&error) {
-        persia_speedy::private::ErrorKind::ExpectedConstant { .. } => {}
+    let value = DerivedStructWithValidation {
+        percent: 100,
+        limit: None,
+        items: vec![],
+        name: "123456789".to_owned(),
+    };
+    let error = value.write_to_vec().unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::LengthIsAboveMaxLengthAttribute {
+            field_name: "name",
+            length: 9,
+            max_length: 8,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let value = DerivedStructWithValidation {
+        percent: 1,
+        limit: None,
+        items: vec![1, 2],
+        name: "a".to_owned(),
+    };
+    let error = value.write_to_vec().unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::ValidationFailed {
+            field_name: None, ..
+        } => {}
         error => panic!("Unexpected error: {:?}", error),
     }
+
+    // Without `validate_on_write` invalid values are written as-is.
+    let value = DerivedStructWithValidationOnlyOnRead { percent: 0 };
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(data, [0]);
+    assert!(DerivedStructWithValidationOnlyOnRead::read_from_buffer(&data).is_err());
 }
 
 #[test]
-fn test_minimum_bytes_needed() {
-    use persia_speedy::{Endianness, Readable};
+fn test_derived_bounds_are_not_inferred_for_skipped_fields() {
+    let value = DerivedStructWithSkippedGeneric {
+        value: 1,
+        cache: NotSpeedy(10),
+    };
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(data, [1]);
+    assert_eq!(
+        DerivedStructWithSkippedGeneric::<NotSpeedy>::read_from_buffer(&data).unwrap(),
+        DerivedStructWithSkippedGeneric {
+            value: 1,
+            cache: NotSpeedy(0)
+        }
+    );
 
+    let value = DerivedStructWithEmptyBound::<NotSpeedy> {
+        value: 1,
+        marker: PhantomData,
+    };
+    let data = value.write_to_vec().unwrap();
     assert_eq!(
-        <DerivedStructWithDefaultOnEof as Readable<Endianness>>::minimum_bytes_needed(),
-        1
+        DerivedStructWithEmptyBound::<NotSpeedy>::read_from_buffer(&data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_derived_struct_with_inferred_generic_bounds() {
+    // Neither `Entity` nor `NotSpeedy` are `Readable` or `Writable`.
+    let value = DerivedStructWithAssociatedTypeAndMarker::<Entity> {
+        id: 0x1234,
+        marker: PhantomData,
+    };
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(data, [0x34, 0x12]);
+    assert_eq!(
+        DerivedStructWithAssociatedTypeAndMarker::<Entity>::read_from_buffer(&data).unwrap(),
+        value
+    );
+
+    let value = DerivedStructWithPhantomData::<NotSpeedy> {
+        value: 1,
+        marker: PhantomData,
+    };
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(data, [1]);
+    assert_eq!(
+        DerivedStructWithPhantomData::<NotSpeedy>::read_from_buffer(&data).unwrap(),
+        value
+    );
+
+    let value = DerivedStructWithGenericCowSlice {
+        values: Cow::Borrowed(&[(1_u8, "a".to_owned())][..]),
+    };
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(data, [1, 0, 0, 0, 1, 1, 0, 0, 0, b'a']);
+    assert_eq!(
+        DerivedStructWithGenericCowSlice::<(u8, String)>::read_from_buffer(&data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_derived_enum_other_with_unknown_tags() {
+    assert_eq!(
+        DerivedEnumWithUnitOther::read_from_buffer(&[9]).unwrap(),
+        DerivedEnumWithUnitOther::Unknown
     );
+    assert_eq!(
+        DerivedEnumWithTagOther::read_from_buffer(&[9]).unwrap(),
+        DerivedEnumWithTagOther::Unknown(9)
+    );
+
+    let error = DerivedEnumWithTagOther::Unknown(256)
+        .write_to_vec()
+        .unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = DerivedEnumWithTagOther::Unknown(0)
+        .write_to_vec()
+        .unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = DerivedLengthDelimitedEnum::Unknown(1, vec![])
+        .write_to_vec()
+        .unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let data = DerivedLengthDelimitedEnumV2::C(0x12345678, true)
+        .write_to_vec()
+        .unwrap();
+    let value = DerivedLengthDelimitedEnum::read_from_buffer(&data).unwrap();
+    assert_eq!(
+        value,
+        DerivedLengthDelimitedEnum::Unknown(2, data[5..].to_vec())
+    );
+    assert_eq!(value.write_to_vec().unwrap(), data);
+    assert_eq!(
+        DerivedLengthDelimitedEnumV2::read_from_buffer(&data).unwrap(),
+        DerivedLengthDelimitedEnumV2::C(0x12345678, true)
+    );
+
+    let data = DerivedLengthDelimitedEnumV2::A(1).write_to_vec().unwrap();
+    assert_eq!(
+        DerivedLengthDelimitedEnum::read_from_buffer(&data).unwrap(),
+        DerivedLengthDelimitedEnum::A(1)
+    );
+}
+
+#[test]
+fn test_derived_length_delimited_enum_skips_extended_variant_body() {
+    let data = DerivedStructWithExtendedLengthDelimitedEnum {
+        value: DerivedLengthDelimitedEnumWithExtendedVariant::A(1, 2),
+        tail: 9,
+    }
+    .write_to_vec()
+    .unwrap();
+
+    assert_eq!(
+        DerivedStructWithLengthDelimitedEnum::read_from_buffer(&data).unwrap(),
+        DerivedStructWithLengthDelimitedEnum {
+            value: DerivedLengthDelimitedEnum::A(1),
+            tail: 9
+        }
+    );
+}
+
+#[test]
+fn test_derived_extensible_struct() {
+    let value = DerivedExtensibleStructV2 {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: vec![4, 5],
+    };
+    let data = (value, 6_u8).write_to_vec().unwrap();
+    assert_eq!(
+        <(DerivedExtensibleStruct, u8)>::read_from_buffer(&data).unwrap(),
+        (DerivedExtensibleStruct { a: 1, b: 2 }, 6)
+    );
+
+    let data = (DerivedExtensibleStruct { a: 1, b: 2 }, 6_u8)
+        .write_to_vec()
+        .unwrap();
+    assert_eq!(
+        <(DerivedExtensibleStructV2, u8)>::read_from_buffer(&data).unwrap(),
+        (
+            DerivedExtensibleStructV2 {
+                a: 1,
+                b: 2,
+                c: 0,
+                d: vec![]
+            },
+            6
+        )
+    );
+
+    let data = [1, 0, 0, 0, 1, 2, 3];
+    let error = DerivedExtensibleStruct::read_from_buffer(&data).unwrap_err();
+    assert!(persia_speedy::IsEof::is_eof(&error));
+}
+
+#[test]
+fn test_derived_nested_extensible_struct_is_written_once() {
+    let value = DerivedDoublyNestedExtensibleStruct {
+        value: CountedWrites(1),
+        inner: DerivedNestedExtensibleStruct {
+            value: CountedWrites(2),
+            inner: DerivedExtensibleStructWithCountedWrites {
+                value: CountedWrites(3),
+            },
+        },
+    };
+
+    let mut data = Vec::new();
+    WRITE_COUNT.with(|count| count.set(0));
+    value.write_to_stream(&mut data).unwrap();
+    assert_eq!(WRITE_COUNT.with(Cell::get), 3);
+
+    WRITE_COUNT.with(|count| count.set(0));
+    assert_eq!(
+        Writable::<Endianness>::bytes_needed(&value).unwrap(),
+        data.len()
+    );
+    assert_eq!(WRITE_COUNT.with(Cell::get), 3);
+
+    assert_eq!(
+        DerivedDoublyNestedExtensibleStruct::read_from_buffer(&data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_derived_nested_length_delimited_enum_is_written_once() {
+    let value = DerivedDoublyNestedLengthDelimitedEnum::A(
+        CountedWrites(1),
+        DerivedNestedLengthDelimitedEnum::A(
+            CountedWrites(2),
+            DerivedLengthDelimitedEnumWithCountedWrites::A(CountedWrites(3)),
+        ),
+    );
+
+    let mut data = Vec::new();
+    WRITE_COUNT.with(|count| count.set(0));
+    value.write_to_stream(&mut data).unwrap();
+    assert_eq!(WRITE_COUNT.with(Cell::get), 3);
+
+    WRITE_COUNT.with(|count| count.set(0));
+    assert_eq!(
+        Writable::<Endianness>::bytes_needed(&value).unwrap(),
+        data.len()
+    );
+    assert_eq!(WRITE_COUNT.with(Cell::get), 3);
+
+    assert_eq!(
+        DerivedDoublyNestedLengthDelimitedEnum::read_from_buffer(&data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_derived_versioned_struct() {
+    use persia_speedy::WithVersion;
+
+    let value = DerivedVersionedStruct {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    };
+
+    let expected = [
+        (
+            1,
+            vec![1, 3],
+            DerivedVersionedStruct {
+                a: 1,
+                b: 0,
+                c: 3,
+                d: 7,
+            },
+        ),
+        (
+            2,
+            vec![1, 2, 0, 3, 4],
+            DerivedVersionedStruct {
+                a: 1,
+                b: 2,
+                c: 3,
+                d: 4,
+            },
+        ),
+        (
+            3,
+            vec![1, 2, 0, 4],
+            DerivedVersionedStruct {
+                a: 1,
+                b: 2,
+                c: 0,
+                d: 4,
+            },
+        ),
+        (
+            4,
+            vec![1, 2, 0],
+            DerivedVersionedStruct {
+                a: 1,
+                b: 2,
+                c: 0,
+                d: 7,
+            },
+        ),
+    ];
+
+    for (version, data, read_back) in expected.iter() {
+        let context = WithVersion::new(Endianness::LittleEndian, *version);
+        let serialized = value.write_to_vec_with_ctx(context).unwrap();
+        assert_eq!(&serialized, data, "version {}", version);
+        assert_eq!(
+            &DerivedVersionedStruct::read_from_buffer_with_ctx(context, &serialized).unwrap(),
+            read_back,
+            "version {}",
+            version
+        );
+    }
+}
+
+#[test]
+fn test_derived_versioned_struct_nested() {
+    use persia_speedy::{Limited, Limits, WithVersion};
+
+    let value = DerivedExtensibleVersionedStruct {
+        values: vec![
+            DerivedVersionedStruct {
+                a: 1,
+                b: 2,
+                c: 3,
+                d: 4,
+            },
+            DerivedVersionedStruct {
+                a: 5,
+                b: 6,
+                c: 7,
+                d: 8,
+            },
+        ],
+        flag: true,
+    };
+
+    let context = WithVersion::new(Endianness::LittleEndian, 1);
+    let data = value.write_to_vec_with_ctx(context).unwrap();
+    assert_eq!(data, [8, 0, 0, 0, 2, 0, 0, 0, 1, 3, 5, 7]);
+
+    let context = Limited::new(
+        WithVersion::new(Endianness::LittleEndian, 2),
+        Limits::new().with_max_total_bytes(100),
+    );
+    let data = value.write_to_vec_with_ctx(context.clone()).unwrap();
+    assert_eq!(
+        DerivedExtensibleVersionedStruct::read_from_buffer_with_ctx(context, &data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_derived_versioned_struct_bytes_needed() {
+    use persia_speedy::WithVersion;
+
+    fn assert_bytes_needed<T: Writable<WithVersion<Endianness>>>(value: &T) {
+        let upper_bound = value.bytes_needed().unwrap();
+        for version in 1..5 {
+            let mut context = WithVersion::new(Endianness::LittleEndian, version);
+            let data = value.write_to_vec_with_ctx(context.clone()).unwrap();
+            assert_eq!(
+                value.bytes_needed_with_ctx(&mut context).unwrap(),
+                data.len()
+            );
+            assert!(data.len() <= upper_bound);
+
+            let mut buffer = vec![0; data.len()];
+            value
+                .write_to_buffer_with_ctx(context.clone(), &mut buffer)
+                .unwrap();
+            assert_eq!(buffer, data);
+        }
+    }
+
+    let value = DerivedVersionedStruct {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    };
+    assert_eq!(
+        Writable::<WithVersion<Endianness>>::bytes_needed(&value).unwrap(),
+        5
+    );
+    assert_bytes_needed(&value);
+
+    let value = DerivedExtensibleVersionedStruct {
+        values: vec![value],
+        flag: true,
+    };
+    assert_bytes_needed(&value);
+}
+
+#[test]
+fn test_zero_non_zero() {
+    let error = NonZeroU32::read_from_buffer(&[0, 0, 0, 0]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::ZeroNonZero => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = NonZeroI16::read_from_buffer(&[0, 0]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::ZeroNonZero => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_vec_with_length_type_u7_read_out_of_range_length() {
+    let error = DerivedStructWithVecWithLengthTypeU7::read_from_buffer(&[0x80]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::OutOfRangeLength => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_prefix_constant_mismatch() {
+    let error = DerivedStructWithConstantPrefixString::read_from_buffer(&[0x41, 0x42]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::InputBufferIsTooSmall { .. } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error =
+        DerivedStructWithConstantPrefixString::read_from_buffer(&[0x41, 0x42, 0x00]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::ExpectedConstant { .. } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_minimum_bytes_needed() {
+    use persia_speedy::{Endianness, Readable};
+
+    assert_eq!(
+        <DerivedStructWithDefaultOnEof as Readable<Endianness>>::minimum_bytes_needed(),
+        1
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_stream_roundtrip() {
+    let (mut client, server) = tokio::io::duplex(3);
+    let writer = async move {
+        let value = DerivedStruct { a: 1, b: 2, c: 3 };
+        value.write_to_async_stream(&mut client).await.unwrap();
+
+        let value = vec!["Hello".to_owned(), "world!".to_owned()];
+        value.write_to_async_stream(&mut client).await.unwrap();
+    };
+
+    let reader = async move {
+        let mut deserializer = persia_speedy::AsyncStreamDeserializer::with_capacity(
+            Endianness::LittleEndian,
+            server,
+            2,
+        );
+        let value: DerivedStruct = deserializer.read_next().await.unwrap().unwrap();
+        assert_eq!(value, DerivedStruct { a: 1, b: 2, c: 3 });
+
+        let value: Vec<String> = deserializer.read_next().await.unwrap().unwrap();
+        assert_eq!(value, vec!["Hello".to_owned(), "world!".to_owned()]);
+
+        let value: Option<Result<u8, _>> = deserializer.read_next().await;
+        assert!(value.is_none());
+    };
+
+    tokio::join!(writer, reader);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_stream_checks_limits_before_buffering() {
+    use persia_speedy::{Limited, Limits};
+
+    let context = Limited::new(
+        Endianness::LittleEndian,
+        Limits::new()
+            .with_max_collection_length(10)
+            .with_max_allocation(1024),
+    );
+
+    let data: &[u8] = &[0xff, 0xff, 0xff, 0x7f, 0x00];
+    let mut deserializer = persia_speedy::AsyncStreamDeserializer::new(context, data);
+    let error = deserializer
+        .read_next::<Vec<u8>>()
+        .await
+        .unwrap()
+        .unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::LimitExceeded { .. } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let (_, buffered) = deserializer.into_inner();
+    assert!(buffered.len() <= 5);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_stream_max_buffer_size() {
+    let data: &[u8] = &[0xff, 0xff, 0xff, 0x7f, 0x00];
+    let mut deserializer =
+        persia_speedy::AsyncStreamDeserializer::new(Endianness::LittleEndian, data)
+            .with_max_buffer_size(1024);
+    let error = deserializer
+        .read_next::<Vec<u8>>()
+        .await
+        .unwrap()
+        .unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::FrameIsTooBig {
+            max_frame_size: 1024,
+            ..
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let data = vec![vec![1_u8; 1000], vec![2_u8; 1000]]
+        .write_to_vec()
+        .unwrap();
+    let mut deserializer = persia_speedy::AsyncStreamDeserializer::with_capacity(
+        Endianness::LittleEndian,
+        &data[4..],
+        16,
+    )
+    .with_max_buffer_size(1004);
+    let value: Vec<u8> = deserializer.read_next().await.unwrap().unwrap();
+    assert_eq!(value, vec![1_u8; 1000]);
+    let value: Vec<u8> = deserializer.read_next().await.unwrap().unwrap();
+    assert_eq!(value, vec![2_u8; 1000]);
+    assert!(deserializer.read_next::<Vec<u8>>().await.is_none());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_stream_does_not_default_on_partial_data() {
+    let (mut client, server) = tokio::io::duplex(1);
+    let writer = async move {
+        let value = DerivedStructWithDefaultOnEof { a: 1, b: 2, c: 3 };
+        value.write_to_async_stream(&mut client).await.unwrap();
+    };
+
+    let reader = async move {
+        let value = DerivedStructWithDefaultOnEof::read_from_async_stream(server)
+            .await
+            .unwrap();
+        assert_eq!(value, DerivedStructWithDefaultOnEof { a: 1, b: 2, c: 3 });
+    };
+
+    tokio::join!(writer, reader);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_stream_truncated_value() {
+    let data: &[u8] = &[1, 0, 0];
+    let error = u32::read_from_async_stream(data).await.unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::UnexpectedEndOfInput => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let data: &[u8] = &[1];
+    let value = DerivedStructWithDefaultOnEof::read_from_async_stream(data)
+        .await
+        .unwrap();
+    assert_eq!(value, DerivedStructWithDefaultOnEof { a: 1, b: 0, c: 0 });
+}
+
+#[test]
+fn test_framed_roundtrip() {
+    use persia_speedy::{FramedReader, FramedWriter, LengthType};
+
+    let mut writer = FramedWriter::new(Endianness::LittleEndian, Vec::new())
+        .with_length_type(LengthType::U64Varint);
+    writer
+        .write_frame(&DerivedStruct { a: 1, b: 2, c: 3 })
+        .unwrap();
+    writer.write_frame("Hello world!").unwrap();
+    let buffer = writer.into_inner();
+    assert_eq!(&buffer[..8], &[7, 1, 2, 0, 3, 0, 0, 0]);
+
+    let mut reader = FramedReader::new(Endianness::LittleEndian, &buffer[..])
+        .with_length_type(LengthType::U64Varint);
+    let value: DerivedStruct = reader.read_frame().unwrap().unwrap();
+    assert_eq!(value, DerivedStruct { a: 1, b: 2, c: 3 });
+    let value: Cow<str> = reader.read_frame().unwrap().unwrap();
+    assert_eq!(value, "Hello world!");
+    assert!(matches!(value, Cow::Borrowed(_)));
+    let value: Option<Result<u8, _>> = reader.read_frame();
+    assert!(value.is_none());
+}
+
+#[test]
+fn test_framed_versioned_values() {
+    use persia_speedy::{FramedReader, FramedWriter, LengthType, WithVersion};
+
+    let context = WithVersion::new(Endianness::LittleEndian, 1);
+    let values = vec![DerivedVersionedStruct {
+        a: 1,
+        b: 0,
+        c: 3,
+        d: 7,
+    }];
+    let mut writer =
+        FramedWriter::new(context.clone(), Vec::new()).with_length_type(LengthType::U64Varint);
+    writer.write_frame(&values).unwrap();
+    let buffer = writer.into_inner();
+    assert_eq!(buffer, [6, 1, 0, 0, 0, 1, 3]);
+
+    let mut reader =
+        FramedReader::new(context, &buffer[..]).with_length_type(LengthType::U64Varint);
+    let deserialized: Vec<DerivedVersionedStruct> = reader.read_frame().unwrap().unwrap();
+    assert_eq!(deserialized, values);
+}
+
+#[test]
+fn test_framed_max_frame_size() {
+    use persia_speedy::{FramedReader, FramedWriter};
+
+    let mut writer = FramedWriter::new(Endianness::LittleEndian, Vec::new()).with_max_frame_size(4);
+    writer.write_frame(&1_u32).unwrap();
+    let error = writer.write_frame(&1_u64).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::FrameIsTooBig {
+            frame_size: 8,
+            max_frame_size: 4,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let buffer = [8, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0];
+    let mut reader =
+        FramedReader::new(Endianness::LittleEndian, &buffer[..]).with_max_frame_size(4);
+    let error = reader.read_frame::<u64>().unwrap().unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::FrameIsTooBig { .. } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_framed_reader_truncated_frame() {
+    use persia_speedy::FramedReader;
+
+    let buffer = [4, 0, 0, 0, 1, 0];
+    let mut reader = FramedReader::new(Endianness::LittleEndian, &buffer[..]);
+    let error = reader.read_frame::<u32>().unwrap().unwrap_err();
+    assert!(persia_speedy::IsEof::is_eof(&error));
+}
+
+#[test]
+fn test_framed_reader_frame_with_trailing_bytes() {
+    use persia_speedy::FramedReader;
+
+    let buffer = [5, 0, 0, 0, 1, 0, 0, 0, 2, 1, 0, 0, 0, 3];
+    let mut reader = FramedReader::new(Endianness::LittleEndian, &buffer[..]);
+    let error = reader.read_frame::<u32>().unwrap().unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::FrameHasTrailingBytes {
+            frame_size: 5,
+            trailing_bytes: 1,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let value: u8 = reader.read_frame().unwrap().unwrap();
+    assert_eq!(value, 3);
+}
+
+#[cfg(feature = "tokio-util")]
+#[test]
+fn test_speedy_codec() {
+    use ::bytes::BytesMut;
+    use persia_speedy::{LengthType, SpeedyCodec};
+    use tokio_util::codec::{Decoder, Encoder};
+
+    let mut codec: SpeedyCodec<DerivedStruct, Endianness> =
+        SpeedyCodec::new(Endianness::LittleEndian).with_length_type(LengthType::U16);
+    let mut buffer = BytesMut::new();
+    codec
+        .encode(DerivedStruct { a: 1, b: 2, c: 3 }, &mut buffer)
+        .unwrap();
+    codec
+        .encode(&DerivedStruct { a: 4, b: 5, c: 6 }, &mut buffer)
+        .unwrap();
+    assert_eq!(buffer.len(), 18);
+    assert_eq!(&buffer[..2], &[7, 0]);
+
+    let mut input = BytesMut::new();
+    let mut values = Vec::new();
+    for &byte in buffer.iter() {
+        input.extend_from_slice(&[byte]);
+        while let Some(value) = codec.decode(&mut input).unwrap() {
+            values.push(value);
+        }
+    }
+
+    assert_eq!(
+        values,
+        vec![
+            DerivedStruct { a: 1, b: 2, c: 3 },
+            DerivedStruct { a: 4, b: 5, c: 6 }
+        ]
+    );
+    assert!(input.is_empty());
+
+    let mut codec: SpeedyCodec<DerivedStruct, Endianness> =
+        SpeedyCodec::new(Endianness::LittleEndian).with_max_frame_size(6);
+    let mut buffer = BytesMut::from(&[7, 0, 0, 0][..]);
+    let error = codec.decode(&mut buffer).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::FrameIsTooBig { .. } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let mut codec: SpeedyCodec<u32, Endianness> = SpeedyCodec::new(Endianness::LittleEndian);
+    let mut buffer = BytesMut::from(&[5, 0, 0, 0, 1, 0, 0, 0, 2][..]);
+    let error = codec.decode(&mut buffer).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::FrameHasTrailingBytes {
+            frame_size: 5,
+            trailing_bytes: 1,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn test_limits_collection_length() {
+    use persia_speedy::{Limit, Limited, Limits};
+
+    let context = Limited::new(
+        Endianness::LittleEndian,
+        Limits::new().with_max_collection_length(2),
+    );
+
+    let data = vec![1_u16, 2].write_to_vec().unwrap();
+    let value: Vec<u16> = Readable::read_from_buffer_with_ctx(context.clone(), &data).unwrap();
+    assert_eq!(value, vec![1, 2]);
+
+    let data = vec![1_u16, 2, 3].write_to_vec().unwrap();
+    let error = Vec::<u16>::read_from_buffer_with_ctx(context.clone(), &data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::LimitExceeded {
+            limit: Limit::CollectionLength,
+            maximum: 2,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let mut map = HashMap::new();
+    map.insert(1_u8, 1_u8);
+    map.insert(2_u8, 2_u8);
+    map.insert(3_u8, 3_u8);
+    let data = map.write_to_vec().unwrap();
+    let error = HashMap::<u8, u8>::read_from_buffer_with_ctx(context, &data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::LimitExceeded {
+            limit: Limit::CollectionLength,
+            ..
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_limits_allocation() {
+    use persia_speedy::{Limit, Limited, Limits};
+
+    let context = Limited::new(
+        Endianness::LittleEndian,
+        Limits::new().with_max_allocation(1024),
+    );
+
+    let data: &[u8] = &[0xff, 0xff, 0xff, 0x0f, 0x00];
+    let error = Vec::<u64>::read_from_stream_buffered_with_ctx(context.clone(), data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::LimitExceeded {
+            limit: Limit::Allocation,
+            maximum: 1024,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let data = vec![vec![0_u8; 600], vec![0_u8; 600]]
+        .write_to_vec()
+        .unwrap();
+    let error = Vec::<Vec<u8>>::read_from_buffer_with_ctx(context, &data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::LimitExceeded {
+            limit: Limit::Allocation,
+            ..
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_limits_total_bytes() {
+    use persia_speedy::{Limit, Limited, Limits, StreamDeserializer};
+
+    let context = Limited::new(
+        Endianness::LittleEndian,
+        Limits::new().with_max_total_bytes(4),
+    );
+    let error = u64::read_from_buffer_with_ctx(context.clone(), &[0; 8]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::LimitExceeded {
+            limit: Limit::TotalBytes,
+            maximum: 4,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    // The limits apply to every value separately.
+    let data: &[u8] = &[1, 0, 0, 0, 2, 0, 0, 0];
+    let values: Vec<u32> = StreamDeserializer::new(context, data)
+        .into_values()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[test]
+fn test_huge_length_from_stream_does_not_preallocate() {
+    let data: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x00];
+    let error = Vec::<u64>::read_from_stream_unbuffered(data).unwrap_err();
+    assert!(persia_speedy::IsEof::is_eof(&error));
+
+    let error = Vec::<String>::read_from_stream_buffered(data).unwrap_err();
+    assert!(persia_speedy::IsEof::is_eof(&error));
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+enum RecursiveExpr {
+    Literal(u32),
+    List(Vec<RecursiveExpr>),
+}
+
+#[test]
+fn test_limits_max_depth() {
+    use persia_speedy::{Limited, Limits};
+
+    let context = Limited::new(Endianness::LittleEndian, Limits::new().with_max_depth(8));
+
+    let value = RecursiveExpr::List(vec![
+        RecursiveExpr::Literal(1),
+        RecursiveExpr::List(vec![RecursiveExpr::Literal(2)]),
+    ]);
+    let data = value.write_to_vec().unwrap();
+    let deserialized: RecursiveExpr =
+        Readable::read_from_buffer_with_ctx(context.clone(), &data).unwrap();
+    assert_eq!(deserialized, value);
+
+    // Each level is a `List` tag followed by a length of one; without a limit
+    // reading this would overflow the stack.
+    let mut data = Vec::new();
+    for _ in 0..1_000_000 {
+        data.extend_from_slice(&1_u32.to_le_bytes());
+        data.extend_from_slice(&1_u32.to_le_bytes());
+    }
+    data.extend_from_slice(&0_u32.to_le_bytes());
+    data.extend_from_slice(&0_u32.to_le_bytes());
+
+    let error = RecursiveExpr::read_from_buffer_with_ctx(context, &data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::RecursionLimitExceeded => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_default_max_depth() {
+    let mut value = RecursiveExpr::Literal(1);
+    for _ in 0..(persia_speedy::DEFAULT_MAX_DEPTH / 2 - 1) {
+        value = RecursiveExpr::List(vec![value]);
+    }
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(RecursiveExpr::read_from_buffer(&data).unwrap(), value);
+
+    let mut data = Vec::new();
+    for _ in 0..1_000_000 {
+        data.extend_from_slice(&1_u32.to_le_bytes());
+        data.extend_from_slice(&1_u32.to_le_bytes());
+    }
+    data.extend_from_slice(&0_u32.to_le_bytes());
+    data.extend_from_slice(&0_u32.to_le_bytes());
+
+    let error = RecursiveExpr::read_from_buffer(&data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::RecursionLimitExceeded => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = RecursiveExpr::read_from_stream_unbuffered(&data[..]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::RecursionLimitExceeded => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_limits_max_depth_is_reset_between_values() {
+    use persia_speedy::{Limited, Limits, StreamDeserializer};
+
+    let context = Limited::new(Endianness::LittleEndian, Limits::new().with_max_depth(4));
+    let value = Some(vec![Some(1_u8)]);
+    let mut data = Vec::new();
+    for _ in 0..3 {
+        data.extend_from_slice(&value.write_to_vec().unwrap());
+    }
+
+    let mut deserializer = StreamDeserializer::new(context, &data[..]);
+    for _ in 0..3 {
+        let deserialized: Option<Vec<Option<u8>>> = deserializer.read_next().unwrap().unwrap();
+        assert_eq!(deserialized, value);
+    }
+    assert!(deserializer
+        .read_next::<Option<Vec<Option<u8>>>>()
+        .is_none());
+
+    let context = Limited::new(Endianness::LittleEndian, Limits::new().with_max_depth(2));
+    let error = Option::<Vec<Option<u8>>>::read_from_buffer_with_ctx(context, &data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::RecursionLimitExceeded => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+#[speedy(tag_type = u8)]
+enum ErrorContextKind {
+    First,
+    Second { name: String },
+}
+
+#[derive(PartialEq, Debug, Readable, Writable)]
+struct ErrorContextOuter {
+    id: u8,
+    items: Vec<ErrorContextKind>,
+}
+
+#[test]
+fn test_mutably_borrowed_ref_cell() {
+    let value = RefCell::new(10_u16);
+    let _borrow = value.borrow_mut();
+    let error = value.write_to_vec().unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::AlreadyMutablyBorrowed => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = Writable::<Endianness>::bytes_needed(&value).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::AlreadyMutablyBorrowed => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_error_context_invalid_ip_and_socket_addr_tag() {
+    let error = std::net::SocketAddr::read_from_buffer(&[2, 0, 0, 0, 0, 0, 0]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert_eq!(error.type_name(), Some("SocketAddr"));
+    assert_eq!(error.tag(), Some(2));
+
+    let error = std::net::IpAddr::read_from_buffer(&[3, 0, 0, 0, 0]).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert_eq!(error.type_name(), Some("IpAddr"));
+    assert_eq!(error.tag(), Some(3));
+}
+
+#[test]
+fn test_error_context_invalid_enum_tag() {
+    let value = ErrorContextOuter {
+        id: 1,
+        items: vec![ErrorContextKind::First, ErrorContextKind::First],
+    };
+    let mut data = value.write_to_vec().unwrap();
+    *data.last_mut().unwrap() = 7;
+
+    let error = ErrorContextOuter::read_from_buffer(&data).unwrap_err();
+    match persia_speedy::private::get_error_kind(&error) {
+        persia_speedy::private::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert_eq!(error.type_name(), Some("ErrorContextKind"));
+    assert_eq!(error.tag(), Some(7));
+    assert_eq!(
+        error.field_path().as_deref(),
+        Some("ErrorContextOuter.items[1]")
+    );
+    assert_eq!(error.offset(), Some(7));
+    assert_eq!(
+        error.to_string(),
+        "invalid enum variant (type: ErrorContextKind, tag: 7, field: ErrorContextOuter.items[1], offset: 7)"
+    );
+}
+
+#[test]
+fn test_error_context_invalid_utf8() {
+    let value = ErrorContextOuter {
+        id: 1,
+        items: vec![
+            ErrorContextKind::First,
+            ErrorContextKind::Second { name: "ab".into() },
+        ],
+    };
+    let mut data = value.write_to_vec().unwrap();
+    *data.last_mut().unwrap() = 0xff;
+
+    let error = ErrorContextOuter::read_from_buffer_owned(&data).unwrap_err();
+    assert_eq!(error.type_name(), Some("ErrorContextKind"));
+    assert_eq!(error.tag(), None);
+    assert_eq!(
+        error.field_path().as_deref(),
+        Some("ErrorContextOuter.items[1].Second.name")
+    );
+    assert_eq!(error.offset(), Some(data.len()));
+
+    let error = ErrorContextOuter::read_from_stream_buffered(&data[..]).unwrap_err();
+    assert_eq!(error.offset(), Some(data.len()));
+}
+
+#[test]
+fn test_error_context_without_derive() {
+    let error =
+        Vec::<Vec<u8>>::read_from_buffer(&[2, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0]).unwrap_err();
+    assert!(persia_speedy::IsEof::is_eof(&error));
+    assert_eq!(error.type_name(), None);
+    assert_eq!(error.field_path().as_deref(), Some("[1]"));
+    assert_eq!(error.offset(), Some(12));
+}
+
+#[test]
+fn test_error_kind_is_public() {
+    let error = u32::read_from_buffer(&[1, 2]).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InputBufferIsTooSmall {
+            actual_size: 2,
+            expected_size: 4,
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[derive(Debug)]
+struct DomainError {
+    code: u32,
+}
+
+impl std::fmt::Display for DomainError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "domain error {}", self.code)
+    }
+}
+
+impl std::error::Error for DomainError {}
+
+#[test]
+fn test_custom_error_downcast() {
+    let error = persia_speedy::Error::custom_error(DomainError { code: 42 });
+    match error.kind() {
+        persia_speedy::ErrorKind::Custom(_) => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert_eq!(error.downcast_ref::<DomainError>().unwrap().code, 42);
+    assert_eq!(error.to_string(), "domain error 42");
+    assert!(std::error::Error::source(&error).is_some());
+    assert!(!persia_speedy::IsEof::is_eof(&error));
+
+    let error = persia_speedy::Error::custom("something went wrong");
+    assert!(error.downcast_ref::<DomainError>().is_none());
+    assert_eq!(error.to_string(), "something went wrong");
+
+    let error: std::io::Error = persia_speedy::Error::custom_error(DomainError { code: 7 }).into();
+    assert_eq!(error.kind(), std::io::ErrorKind::Other);
+    assert_eq!(
+        error
+            .get_ref()
+            .unwrap()
+            .downcast_ref::<DomainError>()
+            .unwrap()
+            .code,
+        7
+    );
+}
+
+#[test]
+fn test_binary_heap_round_trip() {
+    let heap: BinaryHeap<u16> = vec![3, 1, 2].into();
+    let serialized = heap
+        .write_to_vec_with_ctx(Endianness::LittleEndian)
+        .unwrap();
+    assert_eq!(serialized.len(), 4 + 3 * 2);
+
+    let deserialized: BinaryHeap<u16> =
+        Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized).unwrap();
+    assert_eq!(deserialized.into_sorted_vec(), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_locks_serialize_inner_value() {
+    let mutex = Mutex::new(10_u16);
+    let serialized = mutex
+        .write_to_vec_with_ctx(Endianness::LittleEndian)
+        .unwrap();
+    assert_eq!(serialized, [10, 0]);
+
+    let deserialized: Mutex<u16> =
+        Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized).unwrap();
+    assert_eq!(deserialized.into_inner().unwrap(), 10);
+
+    let lock = RwLock::new(10_u16);
+    let serialized = lock
+        .write_to_vec_with_ctx(Endianness::LittleEndian)
+        .unwrap();
+    assert_eq!(serialized, [10, 0]);
+
+    let deserialized: RwLock<u16> =
+        Readable::read_from_buffer_with_ctx(Endianness::LittleEndian, &serialized).unwrap();
+    assert_eq!(deserialized.into_inner().unwrap(), 10);
+}
+
+#[test]
+fn test_bound_with_invalid_tag() {
+    let error =
+        Bound::<u16>::read_from_buffer_with_ctx(Endianness::LittleEndian, &[3, 0, 0]).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InvalidEnumVariant => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+    assert_eq!(error.tag(), Some(3));
+}
+
+#[test]
+fn test_array_read_errors() {
+    let error =
+        <[u16; 4]>::read_from_buffer_with_ctx(Endianness::LittleEndian, &[1, 0, 2]).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InputBufferIsTooSmall { .. } => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+
+    let error = <[String; 2]>::read_from_buffer_with_ctx(
+        Endianness::LittleEndian,
+        &[1, 0, 0, 0, b'A', 1, 0, 0, 0, 0xff],
+    )
+    .unwrap_err();
+    assert_eq!(error.field_path().as_deref(), Some("[1]"));
+}
+
+#[test]
+fn test_borrowed_fields() {
+    let original = DerivedStructWithBorrowedFields {
+        data: &[1, 2, 3],
+        name: "Hello",
+        raw: &[4, 5],
+    };
+
+    let serialized = original.write_to_vec_with_ctx(Endianness::NATIVE).unwrap();
+    let deserialized: DerivedStructWithBorrowedFields =
+        Readable::read_from_buffer_with_ctx(Endianness::NATIVE, &serialized).unwrap();
+    assert_eq!(original, deserialized);
+    assert_eq!(
+        deserialized.name.as_ptr(),
+        serialized[4 + 3 * 2 + 4..].as_ptr()
+    );
+}
+
+#[test]
+fn test_borrowed_slice_errors() {
+    let foreign_endianness = match Endianness::NATIVE {
+        Endianness::LittleEndian => Endianness::BigEndian,
+        Endianness::BigEndian => Endianness::LittleEndian,
+    };
+
+    let serialized = (&[1_u16, 2][..])
+        .write_to_vec_with_ctx(foreign_endianness)
+        .unwrap();
+    let error = <&[u16]>::read_from_buffer_with_ctx(foreign_endianness, &serialized).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::BorrowNeedsEndiannessConversion => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+
+    let serialized = (0_u8, &[1_u16, 2][..])
+        .write_to_vec_with_ctx(Endianness::NATIVE)
+        .unwrap();
+    let error =
+        <(u8, &[u16])>::read_from_buffer_with_ctx(Endianness::NATIVE, &serialized).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::UnalignedBorrow => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+
+    let serialized = "Hello".write_to_vec_with_ctx(Endianness::NATIVE).unwrap();
+    let error = <&str>::read_from_stream_unbuffered_with_ctx(Endianness::NATIVE, &serialized[..])
+        .unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InputIsNotBorrowable => {}
+        kind => panic!("unexpected error kind: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_derived_struct_with_bits() {
+    for &endianness in &[Endianness::LittleEndian, Endianness::BigEndian] {
+        let value = DerivedStructWithWideBits {
+            a: 5,
+            b: 0xFEDC_BA98_7654_3210,
+            c: -2,
+            d: -(1 << 32),
+        };
+        let data = value.write_to_vec_with_ctx(endianness).unwrap();
+        assert_eq!(data.len(), 21);
+        assert_eq!(
+            DerivedStructWithWideBits::read_from_buffer_with_ctx(endianness, &data).unwrap(),
+            value
+        );
+    }
+
+    let value = DerivedStructWithBits {
+        flag: false,
+        kind: DerivedBitsEnum::A,
+        level: 16,
+        offset: 0,
+        value: 0,
+        wide: 0,
+    };
+    let error = value.write_to_vec().unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::ValueDoesNotFitInBits { bits: 4 } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let value = DerivedStructWithWideBits {
+        a: 0,
+        b: 0,
+        c: 0,
+        d: 1 << 32,
+    };
+    let error = value.write_to_vec().unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::ValueDoesNotFitInBits { bits: 33 } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let data = [0b0000_0110, 0, 0, 0, 0, 0];
+    let error = DerivedStructWithBits::read_from_buffer(&data).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::InvalidEnumVariant => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let data = [0b1011_0000, 0, 0, 0, 0, 0];
+    let error = DerivedStructWithBits::read_from_buffer(&data).unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::ValueIsNotInRangeAttribute {
+            field_name: "level",
+        } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let value = DerivedStructWithCustomBits {
+        a: DerivedEnumWithCustomBits::A,
+        b: DerivedEnumWithCustomBits::B,
+    };
+    let data = value.write_to_vec().unwrap();
+    assert_eq!(data, [0b01]);
+    assert_eq!(
+        DerivedStructWithCustomBits::read_from_buffer(&data).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_bits_count_above_64() {
+    use persia_speedy::{BitReader, BitWriter};
+
+    struct TooManyBits;
+
+    impl<'a, C: Context> Readable<'a, C> for TooManyBits {
+        fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+            BitReader::new(reader).read_bits(65)?;
+            Ok(TooManyBits)
+        }
+    }
+
+    impl<C: Context> Writable<C> for TooManyBits {
+        fn write_to<T: ?Sized + Writer<C>>(&self, writer: &mut T) -> Result<(), C::Error> {
+            BitWriter::new(writer).write_bits(65, 0)
+        }
+    }
+
+    let error = TooManyBits.write_to_vec().unwrap_err();
+    match error.kind() {
+        persia_speedy::ErrorKind::TooManyBits { bits: 65 } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    let error = TooManyBits::read_from_buffer(&[0; 16]).err().unwrap();
+    match error.kind() {
+        persia_speedy::ErrorKind::TooManyBits { bits: 65 } => {}
+        error => panic!("Unexpected error: {:?}", error),
+    }
 }